@@ -0,0 +1,59 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use parse_mediawiki_sql::{iterate_sql_insertions, raw_tuples, schemas::Redirect};
+use std::fmt::Write as _;
+
+/// Generates a `redirect` table dump with `rows` rows,
+/// split into `INSERT` statements of `rows_per_statement` rows each.
+fn redirect_sql(rows: usize, rows_per_statement: usize) -> String {
+    let mut sql = String::from("-- MySQL dump\n");
+    for id in 1..=rows {
+        sql.push_str(if id % rows_per_statement == 1 || rows_per_statement == 1 {
+            "INSERT INTO `redirect` VALUES "
+        } else {
+            ","
+        });
+        write!(sql, "({},0,'Redirect_target_{}','','')", id, id).unwrap();
+        if id % rows_per_statement == 0 || id == rows {
+            sql.push_str(";\n");
+        }
+    }
+    sql.push_str("/*!40000 ALTER TABLE `redirect` ENABLE KEYS */;\n");
+    sql
+}
+
+const ROWS: usize = 100_000;
+const ROWS_PER_STATEMENT: [usize; 4] = [1, 10, 1000, ROWS];
+
+/// Parses rows into `Redirect`s, with different numbers of rows per statement.
+fn parse_rows(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse redirect rows per statement");
+    group.throughput(Throughput::Elements(ROWS as u64));
+    for rows_per_statement in ROWS_PER_STATEMENT {
+        let sql = redirect_sql(ROWS, rows_per_statement);
+        group.bench_function(rows_per_statement.to_string(), |b| {
+            b.iter(|| {
+                iterate_sql_insertions::<Redirect>(sql.as_bytes())
+                    .into_iter()
+                    .count()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Splits the rows without parsing the fields,
+/// so that the time spent between rows and statements makes up more of the total.
+fn split_rows(c: &mut Criterion) {
+    let mut group = c.benchmark_group("split redirect rows per statement");
+    group.throughput(Throughput::Elements(ROWS as u64));
+    for rows_per_statement in ROWS_PER_STATEMENT {
+        let sql = redirect_sql(ROWS, rows_per_statement);
+        group.bench_function(rows_per_statement.to_string(), |b| {
+            b.iter(|| raw_tuples(sql.as_bytes()).count())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse_rows, split_rows);
+criterion_main!(benches);
@@ -0,0 +1,124 @@
+/*!
+Benchmarks for parsing generated dumps of the `page`, `categorylinks`, and `pagelinks` tables.
+
+Each table is parsed twice, once to report rows per second and once to report bytes per second.
+Run them with `cargo bench --bench tables`, or a single table with, for example,
+`cargo bench --bench tables -- categorylinks`.
+Criterion saves the results in `target/criterion` and compares later runs to them.
+*/
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use parse_mediawiki_sql::{
+    iterate_sql_insertions,
+    schemas::{CategoryLink, Page, PageLink},
+    FromSqlTuple,
+};
+use std::fmt::Write as _;
+
+const ROWS: usize = 50_000;
+const ROWS_PER_STATEMENT: usize = 1000;
+
+/// Generates a dump of `table` with `ROWS` rows,
+/// split into `INSERT` statements of `ROWS_PER_STATEMENT` rows each.
+fn table_sql(table: &str, mut write_row: impl FnMut(&mut String, usize)) -> String {
+    let mut sql = String::from("-- MySQL dump\n");
+    for id in 1..=ROWS {
+        if id % ROWS_PER_STATEMENT == 1 {
+            write!(sql, "INSERT INTO `{}` VALUES ", table).unwrap();
+        } else {
+            sql.push(',');
+        }
+        write_row(&mut sql, id);
+        if id % ROWS_PER_STATEMENT == 0 || id == ROWS {
+            sql.push_str(";\n");
+        }
+    }
+    writeln!(sql, "/*!40000 ALTER TABLE `{}` ENABLE KEYS */;", table).unwrap();
+    sql
+}
+
+/// Titles of varying length, some with escaped quotes and non-ASCII characters.
+fn title(id: usize) -> String {
+    match id % 4 {
+        0 => format!("Word_{}", id),
+        1 => format!("Appendix:Glossary/{}", id),
+        2 => format!("l\\'{}", id),
+        _ => format!("Ελληνικά_{}", id),
+    }
+}
+
+fn page_sql() -> String {
+    table_sql("page", |sql, id| {
+        write!(
+            sql,
+            "({},{},'{}',{},0,0.{:06},'20230102030405',{},{},{},'wikitext',NULL)",
+            id,
+            id % 3 * 2,
+            title(id),
+            (id % 5 == 0) as u8,
+            id * 7919 % 1_000_000,
+            if id % 2 == 0 {
+                "'20230102030405'"
+            } else {
+                "NULL"
+            },
+            id * 3,
+            id % 4096,
+        )
+        .unwrap()
+    })
+}
+
+fn categorylinks_sql() -> String {
+    table_sql("categorylinks", |sql, id| {
+        write!(
+            sql,
+            "({},'English_nouns','{}\\n{}','2023-01-02 03:04:05','','uca-default-u-kn','page')",
+            id,
+            title(id).to_uppercase(),
+            title(id),
+        )
+        .unwrap()
+    })
+}
+
+fn pagelinks_sql() -> String {
+    table_sql("pagelinks", |sql, id| {
+        write!(sql, "({},{},{})", id / 10 + 1, id % 3 * 2, id * 31 % ROWS).unwrap()
+    })
+}
+
+/// Counts the rows in `sql` parsed as `T`, reporting rows per second and bytes per second.
+fn bench_table<'input, T>(c: &mut Criterion, table: &str, sql: &'input str)
+where
+    T: FromSqlTuple<'input> + 'input,
+{
+    let mut group = c.benchmark_group(table);
+    let count = || {
+        let count = iterate_sql_insertions::<T>(sql.as_bytes())
+            .into_iter()
+            .count();
+        assert_eq!(count, ROWS);
+        count
+    };
+    group.throughput(Throughput::Elements(ROWS as u64));
+    group.bench_function("rows", |b| b.iter(count));
+    group.throughput(Throughput::Bytes(sql.len() as u64));
+    group.bench_function("bytes", |b| b.iter(count));
+    group.finish();
+}
+
+fn parse_page(c: &mut Criterion) {
+    bench_table::<Page>(c, "page", &page_sql());
+}
+
+fn parse_categorylinks(c: &mut Criterion) {
+    bench_table::<CategoryLink>(c, "categorylinks", &categorylinks_sql());
+}
+
+fn parse_pagelinks(c: &mut Criterion) {
+    bench_table::<PageLink>(c, "pagelinks", &pagelinks_sql());
+}
+
+criterion_group!(benches, parse_page, parse_categorylinks, parse_pagelinks);
+criterion_main!(benches);
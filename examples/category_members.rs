@@ -13,7 +13,7 @@ use std::{
 fn main() -> Result<()> {
     let mut args = pico_args::Arguments::from_env();
 
-    #[allow(clippy::redundant_closure)]
+    #[allow(clippy::redundant_closure, clippy::unnecessary_fallible_conversions)]
     let mut get_arg = |keys: [&'static str; 2], default: &'static str| {
         args.value_from_os_str(keys, |opt| PathBuf::try_from(opt))
             .unwrap_or_else(|_| default.into())
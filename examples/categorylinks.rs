@@ -1,5 +1,4 @@
 use anyhow::Result;
-use bstr::ByteSlice;
 use pico_args::Arguments;
 use std::{collections::BTreeMap as Map, convert::TryFrom, path::PathBuf};
 
@@ -7,10 +6,10 @@ use parse_mediawiki_sql::{
     field_types::PageTitle,
     iterate_sql_insertions,
     schemas::{CategoryLink, Page},
-    utils::{memory_map, Mmap, NamespaceMap, NamespaceMapExt as _},
+    utils::{assert_clean_finish, memory_map, Mmap, NamespaceMap, NamespaceMapExt as _},
 };
 
-#[allow(clippy::redundant_closure)]
+#[allow(clippy::redundant_closure, clippy::unnecessary_fallible_conversions)]
 fn opt_path_from_args(args: &mut Arguments, keys: [&'static str; 2]) -> Result<Option<PathBuf>> {
     Ok(args.opt_value_from_os_str(keys, |opt| PathBuf::try_from(opt))?)
 }
@@ -106,12 +105,7 @@ fn main() -> Result<()> {
     );
     serde_json::to_writer(std::io::stdout(), &page_to_categories).unwrap();
 
-    assert_eq!(
-        category_links
-            .finish()
-            .map(|(input, _)| input.chars().take(4).collect::<String>()),
-        Ok(";\n/*".into())
-    );
+    assert_clean_finish(category_links).map_err(|e| anyhow::anyhow!("{}", e))?;
 
     Ok(())
 }
@@ -124,6 +124,16 @@ fn main() -> anyhow::Result<()> {
 
     let script = unsafe { memory_map(sql_path)? };
 
+    // The layout of imagelinks changed when it was migrated to the linktarget table.
+    if table == "imagelinks" {
+        use parse_mediawiki_sql::schemas::{ImageLink, ImageLinkLayout, ImageLinkTarget};
+        match ImageLinkLayout::detect(&script) {
+            Some(ImageLinkLayout::LinkTarget) => print_row_count::<ImageLinkTarget>(&script),
+            _ => print_row_count::<ImageLink>(&script),
+        }
+        return Ok(());
+    }
+
     do_with_table! {
         print_row_count::<
             match table {
@@ -0,0 +1,86 @@
+use anyhow::Result;
+use parse_mediawiki_sql::{
+    iterate_sql_insertions,
+    schemas::{LinkTarget, PageLink},
+    utils::{assert_clean_finish, memory_map},
+};
+use std::{
+    collections::HashMap as Map,
+    convert::TryFrom,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+/// Writes a tab-separated edge list with one line for each row in `pagelinks.sql`,
+/// containing the ID of the linking page and the namespace and title of the link target,
+/// which is looked up in `linktarget.sql`.
+fn write_link_graph<W: Write>(
+    page_links_sql: &[u8],
+    link_target_sql: &[u8],
+    mut out: W,
+) -> Result<()> {
+    let mut link_targets = iterate_sql_insertions::<LinkTarget>(link_target_sql);
+    let link_target_titles: Map<_, _> = (&mut link_targets)
+        .map(
+            |LinkTarget {
+                 id,
+                 namespace,
+                 title,
+             }| (id, (namespace, title)),
+        )
+        .collect();
+    assert_clean_finish(link_targets).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let mut page_links = iterate_sql_insertions::<PageLink>(page_links_sql);
+    for PageLink { from, target, .. } in &mut page_links {
+        if let Some((namespace, title)) = link_target_titles.get(&target) {
+            writeln!(
+                out,
+                "{}\t{}\t{}",
+                from.into_inner(),
+                namespace.into_inner(),
+                title.0
+            )?;
+        }
+    }
+    assert_clean_finish(page_links).map_err(|e| anyhow::anyhow!("{}", e))?;
+    out.flush()?;
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let mut args = pico_args::Arguments::from_env();
+
+    #[allow(clippy::redundant_closure, clippy::unnecessary_fallible_conversions)]
+    let mut get_arg = |keys: [&'static str; 2], default: &'static str| {
+        args.value_from_os_str(keys, |opt| PathBuf::try_from(opt))
+            .unwrap_or_else(|_| default.into())
+    };
+
+    let page_links_sql = unsafe { memory_map(get_arg(["-p", "--page-links"], "pagelinks.sql"))? };
+    let link_target_sql =
+        unsafe { memory_map(get_arg(["-l", "--link-target"], "linktarget.sql"))? };
+
+    let stdout = std::io::stdout();
+    write_link_graph(
+        &page_links_sql,
+        &link_target_sql,
+        BufWriter::new(stdout.lock()),
+    )
+}
+
+#[test]
+fn test_link_graph() {
+    let page_links_sql = b"INSERT INTO `pagelinks` VALUES (1,0,10),(1,0,11),(2,4,10),(3,0,99);\n";
+    let link_target_sql =
+        b"INSERT INTO `linktarget` VALUES (10,0,'Main_Page'),(11,10,'en-noun');\n";
+    let mut out = Vec::new();
+    write_link_graph(page_links_sql, link_target_sql, &mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "1\t0\tMain_Page\n1\t10\ten-noun\n2\t0\tMain_Page\n"
+    );
+
+    let truncated = b"INSERT INTO `pagelinks` VALUES (1,0,10),(1,0,";
+    assert!(write_link_graph(truncated, link_target_sql, Vec::new()).is_err());
+}
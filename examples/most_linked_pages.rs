@@ -0,0 +1,104 @@
+use anyhow::Result;
+use parse_mediawiki_sql::{
+    iterate_sql_insertions,
+    schemas::{LinkTarget, PageLink},
+    utils::{assert_clean_finish, memory_map, NamespaceMap, NamespaceMapExt as _},
+};
+use std::{
+    collections::HashMap as Map,
+    convert::TryFrom,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+/// Counts the links to each target in `pagelinks.sql`, looks up the targets in `linktarget.sql`,
+/// and writes the `top` most-linked-to titles with their link counts, separated by a tab,
+/// from most to fewest links.
+fn write_most_linked<W: Write>(
+    page_links_sql: &[u8],
+    link_target_sql: &[u8],
+    namespace_map: &NamespaceMap,
+    top: usize,
+    mut out: W,
+) -> Result<()> {
+    let mut page_links = iterate_sql_insertions::<PageLink>(page_links_sql);
+    let link_counts = (&mut page_links).fold(Map::new(), |mut map, PageLink { target, .. }| {
+        *map.entry(target).or_insert(0usize) += 1;
+        map
+    });
+    assert_clean_finish(page_links).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let mut link_targets = iterate_sql_insertions::<LinkTarget>(link_target_sql);
+    let mut counts: Vec<_> = (&mut link_targets)
+        .filter_map(
+            |LinkTarget {
+                 id,
+                 namespace,
+                 title,
+             }| {
+                link_counts
+                    .get(&id)
+                    .map(|&count| (namespace_map.pretty_title(namespace, &title), count))
+            },
+        )
+        .collect();
+    assert_clean_finish(link_targets).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    counts.sort_by(|(title1, count1), (title2, count2)| {
+        count2.cmp(count1).then_with(|| title1.cmp(title2))
+    });
+    for (title, count) in counts.into_iter().take(top) {
+        writeln!(out, "{}\t{}", title, count)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let mut args = pico_args::Arguments::from_env();
+
+    #[allow(clippy::redundant_closure, clippy::unnecessary_fallible_conversions)]
+    let mut get_path = |keys: [&'static str; 2], default: &'static str| {
+        args.value_from_os_str(keys, |opt| PathBuf::try_from(opt))
+            .unwrap_or_else(|_| default.into())
+    };
+    let page_links_sql = unsafe { memory_map(get_path(["-p", "--page-links"], "pagelinks.sql"))? };
+    let link_target_sql =
+        unsafe { memory_map(get_path(["-l", "--link-target"], "linktarget.sql"))? };
+    let namespace_map = NamespaceMap::from_path(&get_path(
+        ["-s", "--siteinfo-namespaces"],
+        "siteinfo-namespaces.json",
+    ))?;
+    let top = args.opt_value_from_str(["-n", "--top"])?.unwrap_or(100);
+
+    let stdout = std::io::stdout();
+    write_most_linked(
+        &page_links_sql,
+        &link_target_sql,
+        &namespace_map,
+        top,
+        BufWriter::new(stdout.lock()),
+    )
+}
+
+#[test]
+fn test_most_linked_pages() {
+    let page_links_sql = b"INSERT INTO `pagelinks` VALUES (1,0,10),(1,0,11),(2,0,10),(2,0,12);
+INSERT INTO `pagelinks` VALUES (3,4,10),(3,4,11),(4,0,12),(5,0,12),(6,0,13);
+/*!40000 ALTER TABLE `pagelinks` ENABLE KEYS */;
+";
+    let link_target_sql = b"INSERT INTO `linktarget` VALUES (10,0,'Main_Page'),(11,10,'en-noun'),(12,4,'Sandbox'),(13,0,'Orphan');
+/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;
+";
+    let namespace_map = NamespaceMap::from_pairs(
+        [(0, ""), (4, "Wiktionary"), (10, "Template")]
+            .iter()
+            .map(|&(id, name)| (id, name.to_string())),
+    );
+    let mut out = Vec::new();
+    write_most_linked(page_links_sql, link_target_sql, &namespace_map, 3, &mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "Main Page\t3\nWiktionary:Sandbox\t3\nTemplate:en-noun\t2\n"
+    );
+}
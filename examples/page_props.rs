@@ -44,7 +44,7 @@ unsafe fn memory_map_from_args_in_dir(
     Ok(memory_map(path)?)
 }
 
-#[allow(clippy::redundant_closure)]
+#[allow(clippy::redundant_closure, clippy::unnecessary_fallible_conversions)]
 fn opt_path_from_args(args: &mut Arguments, keys: [&'static str; 2]) -> Result<Option<PathBuf>> {
     Ok(args.opt_value_from_os_str(keys, |opt| PathBuf::try_from(opt))?)
 }
@@ -74,9 +74,9 @@ fn count_prop_names(mut args: Arguments) -> Result<()> {
     };
     let name_counts = parse_mediawiki_sql::iterate_sql_insertions(&props_sql).fold(
         Map::new(),
-        |mut map, PageProperty { name, value, .. }| {
-            let utf8 = std::str::from_utf8(&value).is_ok();
-            let name = SmartString::from(name);
+        |mut map, property: PageProperty| {
+            let utf8 = property.value_as_str().is_some();
+            let name = SmartString::from(property.name);
             let entry = map.entry(name).or_insert((0, 0));
             if utf8 {
                 entry.0 += 1;
@@ -178,18 +178,14 @@ pub fn serialize_displaytitles(mut args: Arguments) -> Result<()> {
     )?)?;
     let namespaces = get_namespaces(args, &namespace_map)?;
     let mut id_to_displaytitle = parse_mediawiki_sql::iterate_sql_insertions(&props_sql)
-        .filter_map(
-            |PageProperty {
-                 page, name, value, ..
-             }| {
-                if name == "displaytitle" {
-                    // All displaytitles should be UTF-8.
-                    Some((page, String::from_utf8(value).unwrap()))
-                } else {
-                    None
-                }
-            },
-        )
+        .filter_map(|property: PageProperty| {
+            if property.name == "displaytitle" {
+                // All displaytitles should be UTF-8.
+                Some((property.page, property.value_as_str().unwrap().to_string()))
+            } else {
+                None
+            }
+        })
         .collect::<Map<_, _>>();
     let title_to_displaytitle = parse_mediawiki_sql::iterate_sql_insertions(&page_sql).fold(
         Map::new(),
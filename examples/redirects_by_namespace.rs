@@ -41,7 +41,7 @@ fn get_args() -> Result<Args> {
         return Ok(Args::Help);
     }
 
-    #[allow(clippy::redundant_closure)]
+    #[allow(clippy::redundant_closure, clippy::unnecessary_fallible_conversions)]
     fn path_from_args(
         args: &mut Arguments,
         keys: impl Into<Keys>,
@@ -120,25 +120,14 @@ fn main() -> anyhow::Result<()> {
         .collect();
     let mut redirects = iterate_sql_insertions::<Redirect>(&redirect_sql);
     let source_to_target: Map<_, _> = redirects
-        .filter_map(
-            |Redirect {
-                 from,
-                 title,
-                 namespace,
-                 ..
-             }| {
-                id_to_title
-                    .get(&from)
-                    .map(|from| (from, (namespace, title)))
-            },
-        )
+        .filter_map(|redirect| {
+            id_to_title
+                .get(&redirect.from)
+                .map(|from| (from, namespace_map.redirect_target(&redirect)))
+        })
         .collect();
     for (k, v) in source_to_target {
-        println!(
-            "{}\t{}",
-            namespace_map.pretty_title(k.0, &k.1),
-            namespace_map.pretty_title(v.0, &v.1)
-        );
+        println!("{}\t{}", namespace_map.pretty_title(k.0, &k.1), v);
     }
     Ok(())
 }
@@ -0,0 +1,50 @@
+use anyhow::Result;
+use parse_mediawiki_sql::{
+    iterate_sql_insertions, json::write_json_lines, schemas::Redirect, utils::memory_map,
+};
+use std::{
+    convert::TryFrom,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+/// Writes one JSON object for each row in `redirect.sql`, with the keys
+/// `from`, `namespace`, `title`, `interwiki`, and `fragment`,
+/// and returns the number of redirects written.
+fn write_redirects<W: Write>(redirect_sql: &[u8], out: W) -> Result<usize> {
+    let mut redirects = iterate_sql_insertions::<Redirect>(redirect_sql);
+    let count = write_json_lines(out, &mut redirects)?;
+    redirects.finish().map_err(|e| anyhow::anyhow!("{}", e))?;
+    Ok(count)
+}
+
+fn main() -> Result<()> {
+    let mut args = pico_args::Arguments::from_env();
+    #[allow(clippy::redundant_closure, clippy::unnecessary_fallible_conversions)]
+    let redirect_path = args
+        .opt_free_from_os_str(|opt| PathBuf::try_from(opt))?
+        .unwrap_or_else(|| "redirect.sql".into());
+    let redirect_sql = unsafe { memory_map(redirect_path)? };
+
+    let stdout = std::io::stdout();
+    let count = write_redirects(&redirect_sql, BufWriter::new(stdout.lock()))?;
+    eprintln!("wrote {} redirects", count);
+    Ok(())
+}
+
+#[test]
+fn test_redirects_jsonl() {
+    let redirect_sql = br"-- MySQL dump
+INSERT INTO `redirect` VALUES (1,0,'Target','',''),(2,10,'en-noun','','Usage'),(3,0,'Foo','wikt',NULL);
+/*!40000 ALTER TABLE `redirect` ENABLE KEYS */;
+";
+    let mut out = Vec::new();
+    assert_eq!(write_redirects(redirect_sql, &mut out).unwrap(), 3);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        r#"{"from":1,"namespace":0,"title":"Target","interwiki":"","fragment":""}
+{"from":2,"namespace":10,"title":"en-noun","interwiki":"","fragment":"Usage"}
+{"from":3,"namespace":0,"title":"Foo","interwiki":"wikt","fragment":null}
+"#
+    );
+}
@@ -1,24 +1,10 @@
-use bstr::ByteSlice;
-use nom::combinator::ParserIterator;
 use parse_mediawiki_sql::{
     field_types::{PageNamespace, PageTitle},
     iterate_sql_insertions,
     schemas::{Page, Redirect},
-    utils::memory_map,
+    utils::{assert_clean_finish, memory_map},
 };
-use std::{collections::BTreeMap as Map, fmt::Display, path::PathBuf};
-
-fn check_parser_finish<E: Display, F>(parser: ParserIterator<&[u8], E, F>) {
-    match parser.finish() {
-        Ok((input, _)) => {
-            assert_eq!(input.chars().take(4).collect::<String>(), ";\n/*");
-        }
-        Err(nom::Err::Incomplete(_)) => panic!("incomplete input"),
-        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
-            panic!("{}", e);
-        }
-    }
-}
+use std::{collections::BTreeMap as Map, path::PathBuf};
 
 // Expects page.sql and redirect.sql in the current directory.
 // Generates JSON: { target: [source1, source2, source3, ...], ...}
@@ -39,7 +25,6 @@ fn main() -> anyhow::Result<()> {
         )?
     };
     let mut pages = iterate_sql_insertions::<Page>(&page_sql);
-    let template_namespace = PageNamespace(10);
     // This works if every template redirect in redirect.sql is also marked
     // as a redirect in page.sql.
     let id_to_title: Map<_, _> = pages
@@ -48,11 +33,11 @@ fn main() -> anyhow::Result<()> {
                  namespace,
                  is_redirect,
                  ..
-             }| *is_redirect && *namespace == template_namespace,
+             }| *is_redirect && *namespace == PageNamespace::TEMPLATE,
         )
         .map(|Page { id, title, .. }| (id, title))
         .collect();
-    check_parser_finish(pages);
+    assert_clean_finish(pages).map_err(|e| anyhow::anyhow!("{}", e))?;
     let mut redirects = iterate_sql_insertions::<Redirect>(&redirect_sql);
     let target_to_sources: Map<_, _> = redirects
         .filter_map(
@@ -72,6 +57,6 @@ fn main() -> anyhow::Result<()> {
             map
         });
     serde_json::to_writer(std::io::stdout(), &target_to_sources).unwrap();
-    check_parser_finish(redirects);
+    assert_clean_finish(redirects).map_err(|e| anyhow::anyhow!("{}", e))?;
     Ok(())
 }
@@ -0,0 +1,79 @@
+use anyhow::Result;
+use parquet::arrow::ArrowWriter;
+use parse_mediawiki_sql::{
+    iterate_sql_insertions,
+    record_batch::{to_record_batch, ToRecordBatch},
+    schemas::Redirect,
+    utils::{assert_clean_finish, memory_map},
+};
+use std::{convert::TryFrom, fs::File, io::Write, path::PathBuf, sync::Arc};
+
+const BATCH_SIZE: usize = 65_536;
+
+/// Writes the rows in `redirect.sql` to a Parquet file with the columns
+/// `from`, `namespace`, `title`, `interwiki`, and `fragment`,
+/// converting them to Arrow record batches of `BATCH_SIZE` rows at a time.
+/// Returns the number of rows written.
+fn write_redirects<W: Write + Send>(redirect_sql: &[u8], out: W) -> Result<usize> {
+    let mut writer = ArrowWriter::try_new(out, Arc::new(Redirect::schema()), None)?;
+    let mut rows = iterate_sql_insertions::<Redirect>(redirect_sql);
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    let mut count = 0;
+    loop {
+        batch.extend((&mut rows).take(BATCH_SIZE));
+        if batch.is_empty() {
+            break;
+        }
+        count += batch.len();
+        writer.write(&to_record_batch(&batch))?;
+        batch.clear();
+    }
+    assert_clean_finish(rows).map_err(|e| anyhow::anyhow!("{}", e))?;
+    writer.close()?;
+    Ok(count)
+}
+
+fn main() -> Result<()> {
+    let mut args = pico_args::Arguments::from_env();
+
+    #[allow(clippy::redundant_closure, clippy::unnecessary_fallible_conversions)]
+    let mut get_arg = |keys: [&'static str; 2], default: &'static str| {
+        args.value_from_os_str(keys, |opt| PathBuf::try_from(opt))
+            .unwrap_or_else(|_| default.into())
+    };
+
+    let redirect_sql = unsafe { memory_map(get_arg(["-r", "--redirect"], "redirect.sql"))? };
+    let out = File::create(get_arg(["-o", "--output"], "redirect.parquet"))?;
+    let count = write_redirects(&redirect_sql, out)?;
+    eprintln!("wrote {} rows", count);
+    Ok(())
+}
+
+#[test]
+fn test_to_parquet() {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    let redirect_sql = br"INSERT INTO `redirect` VALUES (1,0,'Target','',''),(2,10,'Other_target',NULL,'Section'),(3,0,'Elsewhere','w','');
+/*!40000 ALTER TABLE `redirect` ENABLE KEYS */;
+";
+    let path = std::env::temp_dir().join(format!("to_parquet_test_{}.parquet", std::process::id()));
+    let count = write_redirects(redirect_sql, File::create(&path).unwrap()).unwrap();
+    assert_eq!(count, 3);
+    let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+    let metadata = reader.metadata().file_metadata();
+    assert_eq!(metadata.num_rows(), 3);
+    let columns: Vec<_> = metadata
+        .schema_descr()
+        .columns()
+        .iter()
+        .map(|column| column.name().to_string())
+        .collect();
+    assert_eq!(
+        columns,
+        ["from", "namespace", "title", "interwiki", "fragment"]
+    );
+    std::fs::remove_file(path).unwrap();
+
+    let truncated = b"INSERT INTO `redirect` VALUES (1,0,'Target','',''),(2,10,";
+    assert!(write_redirects(truncated, Vec::new()).is_err());
+}
@@ -0,0 +1,160 @@
+use anyhow::{anyhow, Result};
+use nom::{
+    branch::alt,
+    character::streaming::char,
+    combinator::{map, peek},
+    sequence::terminated,
+};
+use parse_mediawiki_sql::{
+    from_sql::{FromSql, IResult},
+    iterate_tables, parse_create_table, raw_tuples,
+    utils::memory_map,
+};
+use rusqlite::{params_from_iter, types::Value, Connection};
+use std::{convert::TryFrom, path::PathBuf};
+
+/// Parses a field in a SQL tuple as an integer, a float, `NULL`, or a string,
+/// which is converted to text if it is valid UTF-8 and otherwise left as a blob.
+fn value(s: &[u8]) -> IResult<'_, Value> {
+    alt((
+        map(
+            terminated(i64::from_sql, peek(alt((char(','), char(')'))))),
+            Value::Integer,
+        ),
+        map(f64::from_sql, Value::Real),
+        map(<Option<Vec<u8>>>::from_sql, |bytes| match bytes {
+            None => Value::Null,
+            Some(bytes) => match String::from_utf8(bytes) {
+                Ok(text) => Value::Text(text),
+                Err(e) => Value::Blob(e.into_bytes()),
+            },
+        }),
+    ))(s)
+}
+
+/// Parses the fields in a SQL tuple that starts with `(` and ends with `)`.
+fn values(tuple: &[u8]) -> Result<Vec<Value>> {
+    let mut values = Vec::new();
+    let mut rest = &tuple[1..];
+    while rest != b")" {
+        match value(rest) {
+            Ok((after_value, value)) => {
+                values.push(value);
+                rest = after_value.strip_prefix(b",").unwrap_or(after_value);
+            }
+            Err(e) => return Err(anyhow!("{}", e)),
+        }
+    }
+    Ok(values)
+}
+
+/// Gives a column the type affinity in SQLite that preserves its values.
+fn sqlite_type(mysql_type: &str) -> &'static str {
+    if mysql_type.contains("int") {
+        "INTEGER"
+    } else if ["float", "double", "decimal"]
+        .iter()
+        .any(|t| mysql_type.contains(t))
+    {
+        "REAL"
+    } else {
+        "BLOB"
+    }
+}
+
+/// Recreates the table in a SQL dump in a SQLite database,
+/// inserting all the rows inside a single transaction.
+/// Returns the name of the table and the number of rows inserted.
+fn load_table(sql: &[u8], connection: &mut Connection) -> Result<(String, usize)> {
+    let table = iterate_tables(sql)
+        .next()
+        .ok_or_else(|| anyhow!("no table with INSERT statements"))?;
+    let columns = parse_create_table(sql).map_err(|e| anyhow!("{}", e))?;
+    let transaction = connection.transaction()?;
+    transaction.execute(
+        &format!(
+            "CREATE TABLE \"{}\" ({})",
+            table.name,
+            columns
+                .iter()
+                .map(|(name, mysql_type)| format!("\"{}\" {}", name, sqlite_type(mysql_type)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        [],
+    )?;
+    let mut count = 0;
+    {
+        let mut insert = transaction.prepare(&format!(
+            "INSERT INTO \"{}\" VALUES ({})",
+            table.name,
+            vec!["?"; columns.len()].join(", ")
+        ))?;
+        for tuple in raw_tuples(table.sql) {
+            insert.execute(params_from_iter(values(tuple)?))?;
+            count += 1;
+        }
+    }
+    transaction.commit()?;
+    Ok((table.name.to_string(), count))
+}
+
+fn main() -> Result<()> {
+    let mut args = pico_args::Arguments::from_env();
+
+    #[allow(clippy::redundant_closure, clippy::unnecessary_fallible_conversions)]
+    let mut get_arg = |keys: [&'static str; 2], default: &'static str| {
+        args.value_from_os_str(keys, |opt| PathBuf::try_from(opt))
+            .unwrap_or_else(|_| default.into())
+    };
+
+    let sql = unsafe { memory_map(get_arg(["-s", "--sql"], "category.sql"))? };
+    let mut connection = Connection::open(get_arg(["-o", "--output"], "dump.sqlite"))?;
+    let (table, count) = load_table(&sql, &mut connection)?;
+    eprintln!("inserted {} rows into {}", count, table);
+    Ok(())
+}
+
+#[test]
+fn test_to_sqlite() {
+    let sql = br"
+DROP TABLE IF EXISTS `category`;
+CREATE TABLE `category` (
+  `cat_id` int(10) unsigned NOT NULL AUTO_INCREMENT,
+  `cat_title` varbinary(255) NOT NULL,
+  `cat_pages` int(11) NOT NULL DEFAULT 0,
+  `cat_subcats` int(11) NOT NULL DEFAULT 0,
+  `cat_files` int(11) NOT NULL DEFAULT 0,
+  PRIMARY KEY (`cat_id`),
+  UNIQUE KEY `cat_title` (`cat_title`),
+  KEY `cat_pages` (`cat_pages`)
+) ENGINE=InnoDB AUTO_INCREMENT=100 DEFAULT CHARSET=binary;
+INSERT INTO `category` VALUES (1,'English_nouns',1000,3,0),(2,'1990s',5,0,0),(3,'Editors\'_notes',-1,0,2);
+/*!40000 ALTER TABLE `category` ENABLE KEYS */;
+";
+    let mut connection = Connection::open_in_memory().unwrap();
+    assert_eq!(
+        load_table(sql, &mut connection).unwrap(),
+        ("category".to_string(), 3)
+    );
+    let count: i64 = connection
+        .query_row("SELECT COUNT(*) FROM category", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 3);
+    let (title, pages): (String, i64) = connection
+        .query_row(
+            "SELECT cat_title, cat_pages FROM category WHERE cat_id = 3",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!((title.as_str(), pages), ("Editors'_notes", -1));
+    let title: String = connection
+        .query_row(
+            "SELECT cat_title FROM category WHERE cat_id = 2",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(title, "1990s");
+}
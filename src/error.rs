@@ -1,5 +1,5 @@
 /*!
-The error types used by [`FromSqlTuple`](crate::FromSqlTuple) and [`FromSql`](crate::from_sql::FromSql).
+The error types used by [`FromSqlTuple`](crate::FromSqlTuple) and [`FromSql`].
 */
 
 use bstr::{BStr, ByteSlice};
@@ -71,7 +71,7 @@ impl<'a> ParseTypeContext<'a> {
     }
 }
 
-/// Error type used by [`FromSql`](crate::from_sql::FromSql).
+/// Error type used by [`FromSql`].
 ///
 /// Keeps a list of the items that were being parsed when an error was encountered.
 /// The [`Display`] implementation prints a backtrace with a snippet of the text that failed to parse.
@@ -256,3 +256,53 @@ impl<'a> Display for Error<'a> {
         }
     }
 }
+
+/// The error returned by [`try_iterate_sql_insertions`](crate::try_iterate_sql_insertions)
+/// when the input can't be a dump created by `mysqldump`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum InputError {
+    /// The input begins with a UTF-8 byte order mark, which `mysqldump` doesn't write,
+    /// so the file has probably been edited and saved by another program.
+    Utf8Bom,
+    /// The input begins with a UTF-16 byte order mark, so the file has probably been
+    /// converted to UTF-16 by another program.
+    Utf16Bom {
+        /// Whether the byte order mark is big-endian (`FE FF`) rather than little-endian (`FF FE`).
+        big_endian: bool,
+    },
+    /// The input doesn't contain an `INSERT INTO` statement.
+    NoInsertions,
+}
+
+impl InputError {
+    /// Checks the beginning of the input for a byte order mark.
+    pub(crate) fn check_bom(sql: &[u8]) -> Result<(), Self> {
+        match sql {
+            [0xEF, 0xBB, 0xBF, ..] => Err(InputError::Utf8Bom),
+            [0xFE, 0xFF, ..] => Err(InputError::Utf16Bom { big_endian: true }),
+            [0xFF, 0xFE, ..] => Err(InputError::Utf16Bom { big_endian: false }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Utf8Bom => write!(
+                f,
+                "the input begins with a UTF-8 byte order mark; \
+                remove it or use the original dump"
+            ),
+            InputError::Utf16Bom { big_endian } => write!(
+                f,
+                "the input begins with a UTF-16{} byte order mark; \
+                convert the file to UTF-8 without a byte order mark or use the original dump",
+                if *big_endian { "BE" } else { "LE" }
+            ),
+            InputError::NoInsertions => write!(f, "the input has no INSERT INTO statement"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
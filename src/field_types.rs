@@ -12,16 +12,17 @@ use nom::{
     error::context,
 };
 
-use std::{convert::TryFrom, ops::Deref, str::FromStr};
+use std::{borrow::Cow, convert::TryFrom, ops::Deref, str::FromStr, time::Duration};
 
 #[cfg(feature = "serialization")]
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{Deserialize, Serialize};
 
 #[cfg(test)]
 use bstr::B;
 
 use crate::from_sql::FromSql;
 use crate::from_sql::IResult;
+use crate::from_sql::SqlDialect;
 
 /// The type used for float fields that are never NaN.
 pub use ordered_float::NotNan;
@@ -29,9 +30,102 @@ pub use ordered_float::NotNan;
 /// The type that [`Timestamp`] derefs to, from `chrono`.
 pub use chrono::NaiveDateTime;
 
+/// A date without a time, from `chrono`, which implements [`FromSql`].
+pub use chrono::NaiveDate;
+
 /// Trait for [`Timestamp`], re-exported from `chrono`.
 pub use chrono::{Datelike, Timelike};
 
+/**
+Converts a field type into one that doesn't borrow from the input,
+for the fields of the owned versions of the [`schemas`](crate::schemas), like
+[`PageOwned`](crate::schemas::PageOwned).
+
+Types that don't borrow are cloned. Borrowed strings and byte strings become
+[`String`]s and [`Vec<u8>`]s, and so do the wrappers around borrowed strings.
+The enums like [`ContentModel`] whose catch-all variant borrows from the input
+keep their variants and only copy the string in the catch-all variant.
+*/
+pub trait ToOwnedField {
+    /// The type of the owned field.
+    type Owned: 'static;
+
+    /// Converts the field.
+    fn to_owned_field(&self) -> Self::Owned;
+}
+
+/// Implements [`ToOwnedField`] for types that don't borrow from the input.
+macro_rules! impl_to_owned_field_by_clone {
+    ($($type_name:ty),+ $(,)?) => {
+        $(
+            impl ToOwnedField for $type_name {
+                type Owned = Self;
+
+                fn to_owned_field(&self) -> Self {
+                    Clone::clone(self)
+                }
+            }
+        )+
+    };
+}
+
+impl_to_owned_field_by_clone!(
+    bool,
+    u8,
+    u16,
+    u32,
+    u64,
+    i8,
+    i16,
+    i32,
+    i64,
+    f32,
+    f64,
+    String,
+    NotNan<f64>,
+    NaiveDate,
+);
+
+impl ToOwnedField for &str {
+    type Owned = String;
+
+    fn to_owned_field(&self) -> String {
+        (*self).to_string()
+    }
+}
+
+impl ToOwnedField for &[u8] {
+    type Owned = Vec<u8>;
+
+    fn to_owned_field(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+impl<T: ToOwnedField> ToOwnedField for Option<T> {
+    type Owned = Option<T::Owned>;
+
+    fn to_owned_field(&self) -> Self::Owned {
+        self.as_ref().map(T::to_owned_field)
+    }
+}
+
+impl<T: ToOwnedField> ToOwnedField for Vec<T> {
+    type Owned = Vec<T::Owned>;
+
+    fn to_owned_field(&self) -> Self::Owned {
+        self.iter().map(T::to_owned_field).collect()
+    }
+}
+
+impl<A: ToOwnedField, B: ToOwnedField> ToOwnedField for (A, B) {
+    type Owned = (A::Owned, B::Owned);
+
+    fn to_owned_field(&self) -> Self::Owned {
+        (self.0.to_owned_field(), self.1.to_owned_field())
+    }
+}
+
 macro_rules! impl_wrapper {
     // $l1 and $l2 must be identical.
     (
@@ -71,6 +165,20 @@ macro_rules! impl_wrapper {
                     Self(val)
                 }
             }
+
+            impl<$l1> ToOwnedField for $wrapper<$l1> {
+                type Owned = <$wrapped_type as ToOwned>::Owned;
+
+                fn to_owned_field(&self) -> Self::Owned {
+                    self.0.to_owned()
+                }
+            }
+
+            impl<$l1> ToTsvField for $wrapper<$l1> {
+                fn write_tsv_field(&self, out: &mut String) {
+                    self.0.write_tsv_field(out)
+                }
+            }
         }
     };
     (
@@ -85,9 +193,13 @@ macro_rules! impl_wrapper {
 
             impl<'input> FromSql<'input> for $wrapper {
                 fn from_sql(s: &'input [u8]) -> IResult<'input, Self> {
+                    Self::from_sql_with_dialect(s, &SqlDialect::MYSQL)
+                }
+
+                fn from_sql_with_dialect(s: &'input [u8], dialect: &SqlDialect) -> IResult<'input, Self> {
                     context(
                         stringify!($wrapper),
-                        map(<$wrapped>::from_sql, $wrapper)
+                        map(|s| <$wrapped>::from_sql_with_dialect(s, dialect), $wrapper)
                     )(s)
                 }
             }
@@ -116,6 +228,14 @@ macro_rules! impl_wrapper {
                     Self(val)
                 }
             }
+
+            impl_to_owned_field_by_clone!($wrapper);
+
+            impl ToTsvField for $wrapper {
+                fn write_tsv_field(&self, out: &mut String) {
+                    self.0.write_tsv_field(out)
+                }
+            }
         }
     };
     (
@@ -153,6 +273,47 @@ field of the `page` table.
     PageNamespace: i32
 }
 
+/// The namespaces that are built into MediaWiki.
+impl PageNamespace {
+    pub const MEDIA: Self = Self(-2);
+    pub const SPECIAL: Self = Self(-1);
+    pub const MAIN: Self = Self(0);
+    pub const TALK: Self = Self(1);
+    pub const USER: Self = Self(2);
+    pub const USER_TALK: Self = Self(3);
+    pub const PROJECT: Self = Self(4);
+    pub const PROJECT_TALK: Self = Self(5);
+    pub const FILE: Self = Self(6);
+    pub const FILE_TALK: Self = Self(7);
+    pub const MEDIAWIKI: Self = Self(8);
+    pub const MEDIAWIKI_TALK: Self = Self(9);
+    pub const TEMPLATE: Self = Self(10);
+    pub const TEMPLATE_TALK: Self = Self(11);
+    pub const HELP: Self = Self(12);
+    pub const HELP_TALK: Self = Self(13);
+    pub const CATEGORY: Self = Self(14);
+    pub const CATEGORY_TALK: Self = Self(15);
+}
+
+#[test]
+fn test_namespace_constants() {
+    for (namespace, id) in &[
+        (PageNamespace::MEDIA, -2),
+        (PageNamespace::SPECIAL, -1),
+        (PageNamespace::MAIN, 0),
+        (PageNamespace::TALK, 1),
+        (PageNamespace::USER, 2),
+        (PageNamespace::PROJECT, 4),
+        (PageNamespace::FILE, 6),
+        (PageNamespace::MEDIAWIKI, 8),
+        (PageNamespace::TEMPLATE, 10),
+        (PageNamespace::CATEGORY, 14),
+        (PageNamespace::CATEGORY_TALK, 15),
+    ] {
+        assert_eq!(namespace.into_inner(), *id);
+    }
+}
+
 impl_wrapper! {
     #[doc="
 Represents the
@@ -291,6 +452,49 @@ field of the `image` table.
     Sha1<'a>: &'a str
 }
 
+impl<'a> Sha1<'a> {
+    /**
+    Decodes the base-36 representation into the 20 bytes of the hash,
+    as they would be written in hexadecimal by `sha1sum`.
+
+    Returns `None` if the string is empty, contains characters other than
+    ASCII letters and digits, or represents a number too large for 160 bits.
+    */
+    pub fn to_bytes(&self) -> Option<[u8; 20]> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let mut bytes = [0u8; 20];
+        for c in self.0.chars() {
+            let mut carry = c.to_digit(36)?;
+            for byte in bytes.iter_mut().rev() {
+                let value = u32::from(*byte) * 36 + carry;
+                *byte = value as u8;
+                carry = value >> 8;
+            }
+            if carry != 0 {
+                return None;
+            }
+        }
+        Some(bytes)
+    }
+}
+
+#[test]
+fn test_sha1_to_bytes() {
+    let hex = |bytes: [u8; 20]| -> String { bytes.iter().map(|b| format!("{:02x}", b)).collect() };
+    // The SHA-1 hash of an empty file.
+    assert_eq!(
+        Sha1("phoiac9h4m842xq45sp7s6u21eteeq1").to_bytes().map(hex),
+        Some("da39a3ee5e6b4b0d3255bfef95601890afd80709".to_string())
+    );
+    assert_eq!(Sha1("0").to_bytes(), Some([0; 20]));
+    assert_eq!(Sha1("").to_bytes(), None);
+    assert_eq!(Sha1("phoiac9h4m842xq45sp7s6u21e-eq1").to_bytes(), None);
+    // 2^160
+    assert_eq!(Sha1("twj4yidkw7a8pn4g709kzmfoaol3x8g").to_bytes(), None);
+}
+
 impl_wrapper! {
     #[doc = "
 Represents
@@ -327,6 +531,57 @@ fn test_copy_for_wrappers() {
     assert_impl_all!(UserGroup: Copy);
 }
 
+/// Implements conversions between `u32` id types and `u64`,
+/// the type usually used for integer columns in columnar formats.
+macro_rules! impl_u64_conversions {
+    ($($wrapper:ident),+ $(,)?) => {
+        $(
+            impl From<$wrapper> for u64 {
+                fn from(val: $wrapper) -> Self {
+                    val.0.into()
+                }
+            }
+
+            /// Fails if the value doesn't fit in a `u32`.
+            impl TryFrom<u64> for $wrapper {
+                type Error = std::num::TryFromIntError;
+
+                fn try_from(val: u64) -> Result<Self, Self::Error> {
+                    u32::try_from(val).map(Self)
+                }
+            }
+        )+
+    };
+}
+
+impl_u64_conversions!(
+    PageId,
+    CategoryId,
+    LogId,
+    ChangeTagId,
+    RevisionId,
+    ChangeTagDefinitionId,
+    RecentChangeId,
+    ExternalLinkId,
+    CommentId,
+    ActorId,
+    PageRestrictionId,
+    UserId,
+    ContentModelId,
+);
+
+#[test]
+fn test_u64_conversions() {
+    assert_eq!(u64::from(PageId(42)), 42);
+    assert_eq!(u64::from(UserId(u32::MAX)), u64::from(u32::MAX));
+    assert_eq!(RevisionId::try_from(42u64), Ok(RevisionId(42)));
+    assert_eq!(
+        ActorId::try_from(u64::from(u32::MAX)),
+        Ok(ActorId(u32::MAX))
+    );
+    assert!(PageId::try_from(u64::from(u32::MAX) + 1).is_err());
+}
+
 /// A [timestamp](https://www.mediawiki.org/wiki/Manual:Timestamp),
 /// represented as a string in the format `'yyyymmddhhmmss'` or `'yyyy-mm-dd hh:mm::ss'`.
 /// Provides the methods of [`NaiveDateTime`] through [`Deref`].
@@ -361,15 +616,395 @@ impl Deref for Timestamp {
     }
 }
 
-/// Represents the
-/// [`pr_expiry`](https://www.mediawiki.org/wiki/Manual:Page_restrictions_table#pr_expiry)
-/// field of the `page_restrictions` table.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/// A [timestamp](https://www.mediawiki.org/wiki/Manual:Timestamp) in a column
+/// that uses an empty string rather than `NULL` to mean that the time is unset,
+/// like the timestamp columns declared `NOT NULL default ''` in older versions of MediaWiki.
+///
+/// Both `''` and `NULL` are parsed as `None`, so this can also be used in place of `Option<Timestamp>`
+/// for columns that contain both. Use `Option<Timestamp>` to reject `''`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct OptionalTimestamp(pub Option<Timestamp>);
+
+impl OptionalTimestamp {
+    pub const fn into_inner(self) -> Option<Timestamp> {
+        self.0
+    }
+}
+
+impl From<OptionalTimestamp> for Option<Timestamp> {
+    fn from(val: OptionalTimestamp) -> Self {
+        val.0
+    }
+}
+
+impl From<Option<Timestamp>> for OptionalTimestamp {
+    fn from(val: Option<Timestamp>) -> Self {
+        Self(val)
+    }
+}
+
+impl<'input> FromSql<'input> for OptionalTimestamp {
+    fn from_sql(s: &'input [u8]) -> IResult<'input, Self> {
+        context(
+            "OptionalTimestamp",
+            alt((
+                map(tag("''"), |_| OptionalTimestamp(None)),
+                map(<Option<Timestamp>>::from_sql, OptionalTimestamp),
+            )),
+        )(s)
+    }
+}
+
+#[test]
+fn test_optional_timestamp() {
+    let timestamp = Timestamp(
+        NaiveDate::from_ymd_opt(2023, 1, 2)
+            .unwrap()
+            .and_hms_opt(3, 4, 5)
+            .unwrap(),
+    );
+    for (s, v) in &[
+        (B("'',"), OptionalTimestamp(None)),
+        (B("NULL,"), OptionalTimestamp(None)),
+        (B("'20230102030405',"), OptionalTimestamp(Some(timestamp))),
+    ] {
+        assert_eq!(OptionalTimestamp::from_sql(s), Ok((B(","), *v)));
+    }
+    assert!(OptionalTimestamp::from_sql(b"'2023',").is_err());
+}
+
+/// A duration stored as a whole number of seconds in an unsigned integer column,
+/// as in some extension tables. Provides the methods of [`Duration`] through [`Deref`].
+/// Serialized as the number of seconds, like the column.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
 #[cfg_attr(
     feature = "serialization",
     derive(Serialize, Deserialize),
-    serde(try_from = "&str", into = "String")
+    serde(from = "u64", into = "u64")
 )]
+pub struct DurationSecs(pub Duration);
+
+impl DurationSecs {
+    pub const fn into_inner(self) -> Duration {
+        self.0
+    }
+}
+
+impl From<DurationSecs> for Duration {
+    fn from(val: DurationSecs) -> Self {
+        val.0
+    }
+}
+
+impl From<Duration> for DurationSecs {
+    fn from(val: Duration) -> Self {
+        Self(val)
+    }
+}
+
+impl From<u64> for DurationSecs {
+    fn from(secs: u64) -> Self {
+        Self(Duration::from_secs(secs))
+    }
+}
+
+/// Drops any fraction of a second, which a parsed duration never has.
+impl From<DurationSecs> for u64 {
+    fn from(val: DurationSecs) -> Self {
+        val.0.as_secs()
+    }
+}
+
+impl<'input> FromSql<'input> for DurationSecs {
+    fn from_sql(s: &'input [u8]) -> IResult<'input, Self> {
+        context("DurationSecs", map(u64::from_sql, DurationSecs::from))(s)
+    }
+}
+
+impl Deref for DurationSecs {
+    type Target = Duration;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[test]
+fn test_duration_secs() {
+    for (s, secs) in &[(B("0,"), 0), (B("86400,"), 86_400), (B("3600)"), 3600)] {
+        let (rest, duration) = DurationSecs::from_sql(s).unwrap();
+        assert_eq!(duration, DurationSecs(Duration::from_secs(*secs)));
+        assert_eq!(rest, &s[s.len() - 1..]);
+    }
+    assert_eq!(DurationSecs::from_sql(b"90,").unwrap().1.as_secs(), 90);
+    for s in &[&b"-1,"[..], b"'60',", b"NULL,"] {
+        assert!(DurationSecs::from_sql(s).is_err());
+    }
+}
+
+#[cfg(feature = "serialization")]
+#[test]
+fn test_duration_secs_serialization() {
+    let duration = DurationSecs(Duration::from_secs(86_400));
+    assert_eq!(serde_json::to_string(&duration).unwrap(), "86400");
+    assert_eq!(
+        serde_json::from_str::<DurationSecs>("86400").unwrap(),
+        duration
+    );
+}
+
+/// A boolean stored as a one-character string, `'Y'` or `'N'`, or `'t'` or `'f'` as in Postgres,
+/// as in some extension tables and tables imported from other databases.
+/// MediaWiki's own boolean columns contain `1` or `0`, which are parsed by [`bool`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct YesNoBool(pub bool);
+
+impl YesNoBool {
+    pub const fn into_inner(self) -> bool {
+        self.0
+    }
+}
+
+impl From<YesNoBool> for bool {
+    fn from(val: YesNoBool) -> Self {
+        val.0
+    }
+}
+
+impl From<bool> for YesNoBool {
+    fn from(val: bool) -> Self {
+        Self(val)
+    }
+}
+
+impl<'input> FromSql<'input> for YesNoBool {
+    fn from_sql(s: &'input [u8]) -> IResult<'input, Self> {
+        context(
+            "'Y', 'N', 't', or 'f'",
+            alt((
+                map(alt((tag("'Y'"), tag("'t'"))), |_| YesNoBool(true)),
+                map(alt((tag("'N'"), tag("'f'"))), |_| YesNoBool(false)),
+            )),
+        )(s)
+    }
+}
+
+#[test]
+fn test_yes_no_bool() {
+    for (s, b) in &[
+        (B("'Y',"), true),
+        (B("'N',"), false),
+        (B("'t',"), true),
+        (B("'f',"), false),
+    ] {
+        assert_eq!(YesNoBool::from_sql(s), Ok((B(","), YesNoBool(*b))));
+    }
+    for s in &[&b"1,"[..], b"'1',", b"'y',", b"'true',", b"NULL,"] {
+        assert!(YesNoBool::from_sql(s).is_err());
+    }
+}
+
+/// A [timestamp](https://www.mediawiki.org/wiki/Manual:Timestamp) like [`Timestamp`],
+/// but represented by the [`time`] crate's [`PrimitiveDateTime`](time::PrimitiveDateTime)
+/// rather than `chrono`'s [`NaiveDateTime`], for use in schemas defined outside this crate.
+/// Provides the methods of [`PrimitiveDateTime`](time::PrimitiveDateTime) through [`Deref`].
+#[cfg(feature = "time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct TimestampTime(pub time::PrimitiveDateTime);
+
+#[cfg(feature = "time")]
+impl<'input> FromSql<'input> for TimestampTime {
+    fn from_sql(s: &'input [u8]) -> IResult<'input, Self> {
+        use time::macros::format_description;
+
+        context(
+            "Timestamp in yyyymmddhhmmss or yyyy-mm-dd hh:mm::ss format",
+            map_res(<&str>::from_sql, |s| {
+                time::PrimitiveDateTime::parse(
+                    s,
+                    if s.len() == 14 {
+                        format_description!("[year][month][day][hour][minute][second]")
+                    } else {
+                        format_description!("[year]-[month]-[day] [hour]:[minute]:[second]")
+                    },
+                )
+                .map(TimestampTime)
+            }),
+        )(s)
+    }
+}
+
+#[cfg(feature = "time")]
+impl Deref for TimestampTime {
+    type Target = time::PrimitiveDateTime;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_timestamp_time() {
+    use time::macros::datetime;
+
+    for s in &[&b"'20230102030405'"[..], b"'2023-01-02 03:04:05'"] {
+        assert_eq!(
+            TimestampTime::from_sql(s),
+            Ok((&b""[..], TimestampTime(datetime!(2023-01-02 03:04:05))))
+        );
+    }
+    for s in &[
+        &b"'2023010203040'"[..],
+        b"'2023-01-02T03:04:05'",
+        b"'20231302030405'",
+    ] {
+        assert!(TimestampTime::from_sql(s).is_err());
+    }
+}
+
+/// A string without escape sequences that can be `NULL`,
+/// for fields in which `NULL` and the empty string have different meanings,
+/// such as [`rd_interwiki`](https://www.mediawiki.org/wiki/Manual:Redirect_table#rd_interwiki).
+/// `NULL` is parsed as `NullableStr(None)` and `''` as `NullableStr(Some(""))`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct NullableStr<'a>(
+    #[cfg_attr(feature = "serialization", serde(borrow))] pub Option<&'a str>,
+);
+
+impl<'a> NullableStr<'a> {
+    pub const fn into_inner(self) -> Option<&'a str> {
+        self.0
+    }
+
+    pub const fn is_null(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// Returns `true` if the string is `NULL` or empty.
+    pub fn is_null_or_empty(&self) -> bool {
+        self.0.unwrap_or_default().is_empty()
+    }
+}
+
+impl<'a> From<NullableStr<'a>> for Option<&'a str> {
+    fn from(val: NullableStr<'a>) -> Self {
+        val.0
+    }
+}
+
+impl<'a> From<Option<&'a str>> for NullableStr<'a> {
+    fn from(val: Option<&'a str>) -> Self {
+        Self(val)
+    }
+}
+
+impl<'a> FromSql<'a> for NullableStr<'a> {
+    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+        context("NullableStr", map(<Option<&str>>::from_sql, NullableStr))(s)
+    }
+}
+
+#[test]
+fn test_nullable_str() {
+    for (s, v) in &[
+        (B("NULL"), NullableStr(None)),
+        (B("''"), NullableStr(Some(""))),
+        (B("'x'"), NullableStr(Some("x"))),
+    ] {
+        assert_eq!(NullableStr::from_sql(s), Ok((B(""), *v)));
+    }
+    assert!(NullableStr(None).is_null());
+    assert!(!NullableStr(Some("")).is_null());
+    assert!(NullableStr(Some("")).is_null_or_empty());
+    assert!(!NullableStr(Some("x")).is_null_or_empty());
+}
+
+/**
+A list of values joined by line breaks in a string, like the
+[`log_params`](https://www.mediawiki.org/wiki/Manual:Logging_table#log_params)
+of old log entries, which were stored as the parameters of the log message
+separated by `\n` before MediaWiki 1.19 switched to serialized PHP arrays.
+For instance, the parameters of an old page move, `'New_title\n1'`,
+are the title the page was moved to and whether a redirect was suppressed.
+
+The string is unescaped and split at each line break, and each piece is parsed with [`FromStr`].
+An empty string is an empty list.
+*/
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct NewlineDelimited<T>(pub Vec<T>);
+
+impl<T> NewlineDelimited<T> {
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> Deref for NewlineDelimited<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, T: FromStr> FromSql<'a> for NewlineDelimited<T> {
+    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+        Self::from_sql_with_dialect(s, &SqlDialect::MYSQL)
+    }
+
+    fn from_sql_with_dialect(s: &'a [u8], dialect: &SqlDialect) -> IResult<'a, Self> {
+        context(
+            "NewlineDelimited",
+            map_res(
+                |s| String::from_sql_with_dialect(s, dialect),
+                |s| {
+                    if s.is_empty() {
+                        Ok(Vec::new())
+                    } else {
+                        s.split('\n').map(str::parse).collect::<Result<_, _>>()
+                    }
+                    .map(NewlineDelimited)
+                },
+            ),
+        )(s)
+    }
+}
+
+#[test]
+fn test_newline_delimited() {
+    assert_eq!(
+        NewlineDelimited::<String>::from_sql(br"'New_title\n1',"),
+        Ok((
+            B(","),
+            NewlineDelimited(vec!["New_title".to_string(), "1".to_string()])
+        ))
+    );
+    assert_eq!(
+        NewlineDelimited::<u32>::from_sql(br"'12\n345',"),
+        Ok((B(","), NewlineDelimited(vec![12, 345])))
+    );
+    assert_eq!(
+        NewlineDelimited::<u32>::from_sql(b"'',"),
+        Ok((B(","), NewlineDelimited(Vec::new())))
+    );
+    assert!(NewlineDelimited::<u32>::from_sql(br"'12\nx',").is_err());
+}
+
+/// Represents the
+/// [`pr_expiry`](https://www.mediawiki.org/wiki/Manual:Page_restrictions_table#pr_expiry)
+/// field of the `page_restrictions` table.
+///
+/// Serialized as an externally tagged enum, so that `Infinity` is `"Infinity"`
+/// and a timestamp is `{"Timestamp":"2021-01-01T00:00:00"}`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub enum Expiry {
     Timestamp(Timestamp),
     Infinity,
@@ -407,388 +1042,814 @@ impl<'input> FromSql<'input> for Expiry {
     }
 }
 
-// #[cfg(feature = "serialization")]
-// impl Serialize for Expiry {
-//     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-//     where
-//         S: Serializer,
-//     {
-//         match self {
-//             Expiry::Timestamp(timestamp) => timestamp.serialize(serializer),
-//             Expiry::Infinity => timestamp.serialize_str("infinity"),
-//         }
-//     }
-// }
-
-// #[cfg(feature = "serialization")]
-// impl<'de> Deserialize<'de> for Expiry {
-//     fn deserialize<D>(deserializer: D) -> Result<Expiry, D::Error>
-//     where
-//         D: Deserializer<'de>,
-//     {
-//         match deserializer.deserialize_str(I32Visitor)? {
-//             "infinity" => Ok(Expiry::Infinity),
-//             s => Ok(Timestamp::from_str(s)?),
-//         }
-//     }
-// }
+#[cfg(feature = "serialization")]
+#[test]
+fn test_expiry_serialization() {
+    let timestamp = Expiry::Timestamp(Timestamp(
+        NaiveDate::from_ymd_opt(2021, 1, 1)
+            .unwrap()
+            .and_hms_opt(12, 30, 0)
+            .unwrap(),
+    ));
+    for (expiry, json) in &[
+        (timestamp, r#"{"Timestamp":"2021-01-01T12:30:00"}"#),
+        (Expiry::Infinity, r#""Infinity""#),
+    ] {
+        assert_eq!(&serde_json::to_string(expiry).unwrap(), json);
+        assert_eq!(&serde_json::from_str::<Expiry>(json).unwrap(), expiry);
+    }
+    assert!(serde_json::from_str::<Expiry>(r#""infinity""#).is_err());
+}
 
-/// Represents the
-/// [`cl_type`](https://www.mediawiki.org/wiki/Manual:Categorylinks_table#cl_type)
-/// field of the `categorylinks` table.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/**
+Defines an enum for a string field with a few usual values and an `Other` variant
+that holds any other value, borrowed from the input when parsed, so that a new value doesn't stop a dump from being parsed.
+
+Generates the conversions from strings and back, the [`FromSql`] implementations
+for the enum and for [`CaseInsensitive`] wrapping it, and the [`ToOwnedField`] implementation,
+all from the same list of variants and strings.
+*/
+macro_rules! string_enum {
+    (
+        $(#[$enum_meta:meta])*
+        $type_name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident => $string:literal,
+            )+
+        }
+    ) => {
+        $(#[$enum_meta])*
+        #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        #[cfg_attr(
+            feature = "serialization",
+            derive(Serialize, Deserialize),
+            serde(from = "Cow<'a, str>", into = "Cow<'a, str>")
+        )]
+        pub enum $type_name<'a> {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )+
+            /// A value other than the usual ones.
+            Other(Cow<'a, str>),
+        }
+
+        impl<'a> $type_name<'a> {
+            /// Returns the string that the value represents.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($type_name::$variant => $string,)+
+                    $type_name::Other(s) => s,
+                }
+            }
+
+            /// Copies the string in [`Other`](Self::Other) if it borrows from the input.
+            pub fn into_owned(self) -> $type_name<'static> {
+                match self {
+                    $($type_name::$variant => $type_name::$variant,)+
+                    $type_name::Other(s) => $type_name::Other(Cow::Owned(s.into_owned())),
+                }
+            }
+        }
+
+        impl<'a> From<Cow<'a, str>> for $type_name<'a> {
+            fn from(s: Cow<'a, str>) -> Self {
+                match &*s {
+                    $($string => $type_name::$variant,)+
+                    _ => $type_name::Other(s),
+                }
+            }
+        }
+
+        impl<'a> From<&'a str> for $type_name<'a> {
+            fn from(s: &'a str) -> Self {
+                Cow::Borrowed(s).into()
+            }
+        }
+
+        impl<'a> From<$type_name<'a>> for Cow<'a, str> {
+            fn from(value: $type_name<'a>) -> Self {
+                match value {
+                    $($type_name::$variant => Cow::Borrowed($string),)+
+                    $type_name::Other(s) => s,
+                }
+            }
+        }
+
+        impl<'a> FromSql<'a> for $type_name<'a> {
+            fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+                context(stringify!($type_name), map(<&str>::from_sql, $type_name::from))(s)
+            }
+        }
+
+        impl<'a> FromSql<'a> for CaseInsensitive<$type_name<'a>> {
+            fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+                context(
+                    concat!(stringify!($type_name), " (case-insensitive)"),
+                    map(<&str>::from_sql, |s| {
+                        CaseInsensitive(
+                            $(
+                                if s.eq_ignore_ascii_case($string) {
+                                    $type_name::$variant
+                                } else
+                            )+
+                            {
+                                $type_name::Other(Cow::Borrowed(s))
+                            },
+                        )
+                    }),
+                )(s)
+            }
+        }
+
+        impl<'a> ToOwnedField for $type_name<'a> {
+            type Owned = $type_name<'static>;
+
+            fn to_owned_field(&self) -> Self::Owned {
+                self.clone().into_owned()
+            }
+        }
+    };
+}
+
+string_enum! {
+    /// Represents the
+    /// [`cl_type`](https://www.mediawiki.org/wiki/Manual:Categorylinks_table#cl_type)
+    /// field of the `categorylinks` table.
+    /// Unrecognized values are kept in [`PageType::Other`],
+    /// so that a new type doesn't stop a dump from being parsed.
+    PageType {
+        Page => "page",
+        Subcat => "subcat",
+        File => "file",
+    }
+}
+
+#[test]
+fn test_page_type() {
+    for (sql, page_type) in &[
+        (&b"'page'"[..], PageType::Page),
+        (b"'subcat'", PageType::Subcat),
+        (b"'file'", PageType::File),
+        (b"'media'", PageType::Other("media".into())),
+    ] {
+        assert_eq!(PageType::from_sql(sql), Ok((&b""[..], page_type.clone())));
+        assert_eq!(format!("'{}'", page_type.as_str()).as_bytes(), *sql);
+    }
+}
+
+string_enum! {
+    /// Represents the
+    /// [`pr_type`](https://www.mediawiki.org/wiki/Manual:Page_restrictions_table#pr_type)
+    /// field of the `page_restrictions` table, the action that is restricted.
+    PageAction {
+        Edit => "edit",
+        Move => "move",
+        Reply => "reply",
+        Upload => "upload",
+        All => "all",
+    }
+}
+
+string_enum! {
+    /// Represents the
+    /// [`pr_level`](https://www.mediawiki.org/wiki/Manual:Page_restrictions_table#pr_level)
+    /// field of the `page_restrictions` table, the group that is allowed
+    /// to perform the action.
+    ProtectionLevel {
+        Autoconfirmed => "autoconfirmed",
+        ExtendedConfirmed => "extendedconfirmed",
+        Sysop => "sysop",
+        TemplateEditor => "templateeditor",
+        EditProtected => "editprotected",
+        EditSemiProtected => "editsemiprotected",
+        /// The result of parsing the empty string after the `=` in `'move=:edit='`.
+        None => "",
+    }
+}
+
+/// Represents the legacy
+/// [`page_restrictions`](https://www.mediawiki.org/wiki/Manual:Page_table#page_restrictions)
+/// field of the `page` table, a string such as `'edit=autoconfirmed:move=sysop'`.
+///
+/// The restrictions are kept in their original order, so that the
+/// [`Display`](std::fmt::Display) impl reproduces the string that was parsed.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
 #[cfg_attr(
     feature = "serialization",
     derive(Serialize, Deserialize),
-    serde(try_from = "&str", into = "&'static str")
+    serde(bound(deserialize = "'de: 'a"))
 )]
-pub enum PageType {
-    Page,
-    Subcat,
-    File,
-}
+pub struct PageRestrictionsOld<'a>(pub Vec<(PageAction<'a>, ProtectionLevel<'a>)>);
 
-/// Returns the unrecognized string as the error value.
-impl<'a> TryFrom<&'a str> for PageType {
+/// Returns the restriction that lacks an `=` as the error value.
+impl<'a> TryFrom<&'a str> for PageRestrictionsOld<'a> {
     type Error = &'a str;
 
     fn try_from(s: &'a str) -> Result<Self, &'a str> {
-        use PageType::*;
-        match s {
-            "page" => Ok(Page),
-            "subcat" => Ok(Subcat),
-            "file" => Ok(File),
-            other => Err(other),
+        if s.is_empty() {
+            return Ok(Self::default());
         }
+        s.split(':')
+            .map(|restriction| {
+                let (action, level) = restriction.split_once('=').ok_or(restriction)?;
+                Ok((PageAction::from(action), ProtectionLevel::from(level)))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
     }
 }
 
-impl From<PageType> for &'static str {
-    fn from(s: PageType) -> &'static str {
-        use PageType::*;
-        match s {
-            Page => "page",
-            Subcat => "subcat",
-            File => "file",
+impl<'a> std::fmt::Display for PageRestrictionsOld<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (action, level)) in self.0.iter().enumerate() {
+            if i != 0 {
+                f.write_str(":")?;
+            }
+            write!(f, "{}={}", action.as_str(), level.as_str())?;
         }
+        Ok(())
     }
 }
 
-impl<'a> FromSql<'a> for PageType {
+impl<'a> FromSql<'a> for PageRestrictionsOld<'a> {
     fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
-        context("PageType", map_res(<&str>::from_sql, PageType::try_from))(s)
+        context(
+            "PageRestrictionsOld",
+            map_res(<&str>::from_sql, PageRestrictionsOld::try_from),
+        )(s)
     }
 }
 
-/// Represents the
-/// [`pr_type`](https://www.mediawiki.org/wiki/Manual:Page_restrictions_table#pr_type)
-/// field of the `page_restrictions` table, the action that is restricted.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
-#[cfg_attr(
-    feature = "serialization",
-    derive(Serialize, Deserialize),
-    serde(from = "&'a str", into = "&'a str")
-)]
-pub enum PageAction<'a> {
-    Edit,
-    Move,
-    Reply,
-    Upload,
-    All,
-    Other(&'a str),
-}
-
-impl<'a> From<&'a str> for PageAction<'a> {
-    fn from(s: &'a str) -> Self {
-        use PageAction::*;
-        match s {
-            "edit" => Edit,
-            "move" => Move,
-            "reply" => Reply,
-            "upload" => Upload,
-            _ => Other(s),
-        }
+#[test]
+fn test_page_restrictions() {
+    use PageAction::*;
+    use ProtectionLevel::*;
+    for (sql, restrictions) in &[
+        ("''", vec![]),
+        ("'edit=autoconfirmed'", vec![(Edit, Autoconfirmed)]),
+        (
+            "'edit=autoconfirmed:move=sysop'",
+            vec![(Edit, Autoconfirmed), (Move, Sysop)],
+        ),
+        ("'move=:edit='", vec![(Move, None), (Edit, None)]),
+        (
+            "'upload=sysop:edit=extendedconfirmed'",
+            vec![(Upload, Sysop), (Edit, ExtendedConfirmed)],
+        ),
+        ("'all=sysop'", vec![(All, Sysop)]),
+    ] {
+        let parsed = PageRestrictionsOld::from_sql(sql.as_bytes()).unwrap().1;
+        assert_eq!(&parsed.0, restrictions);
+        let displayed = format!("'{}'", parsed);
+        assert_eq!(&displayed, sql);
+        assert_eq!(
+            PageRestrictionsOld::from_sql(displayed.as_bytes())
+                .unwrap()
+                .1,
+            parsed
+        );
+    }
+    assert!(PageRestrictionsOld::from_sql(b"'sysop'").is_err());
+}
+
+string_enum! {
+    /// Represents the
+    /// [`page_content_model`](https://www.mediawiki.org/wiki/Manual:Page_table#page_content_model)
+    /// field of the `page` table.
+    ContentModel {
+        Wikitext => "wikitext",
+        Scribunto => "Scribunto",
+        Text => "text",
+        Css => "css",
+        SanitizedCss => "sanitized-css",
+        JavaScript => "javascript",
+        Json => "json",
     }
 }
 
-impl<'a> From<PageAction<'a>> for &'a str {
-    fn from(p: PageAction<'a>) -> Self {
-        use PageAction::*;
-        match p {
-            Edit => "edit",
-            Move => "move",
-            Reply => "reply",
-            Upload => "upload",
-            All => "all",
-            Other(s) => s,
+impl_wrapper! {
+    #[doc = "
+Represents
+[`model_id`](https://www.mediawiki.org/wiki/Manual:Content_models_table#model_id),
+the primary key of the `content_models` table, as well as the
+[`content_model`](https://www.mediawiki.org/wiki/Manual:Content_table#content_model)
+field of the `content` table.
+"]
+    ContentModelId: u32
+}
+
+impl ContentModelId {
+    /// Returns a best-effort guess at the name of the content model,
+    /// using the ids that the common content models have on many wikis.
+    ///
+    /// The ids are not stable: each wiki assigns them as the models are first used.
+    /// Use the wiki's `content_models` table to map ids to names reliably.
+    pub fn as_str(&self) -> Option<&'static str> {
+        match self.0 {
+            1 => Some("wikitext"),
+            2 => Some("javascript"),
+            3 => Some("json"),
+            4 => Some("css"),
+            5 => Some("text"),
+            _ => None,
         }
     }
+
+    /// Returns the [`ContentModel`] corresponding to [`as_str`](Self::as_str),
+    /// which is likewise only a best-effort guess.
+    pub fn content_model(&self) -> Option<ContentModel<'static>> {
+        self.as_str().map(ContentModel::from)
+    }
 }
 
-impl<'a> FromSql<'a> for PageAction<'a> {
-    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
-        map(<&str>::from_sql, PageAction::from)(s)
+#[test]
+fn test_content_model_id() {
+    for (id, name, model) in &[
+        (1, "wikitext", ContentModel::Wikitext),
+        (2, "javascript", ContentModel::JavaScript),
+        (3, "json", ContentModel::Json),
+        (4, "css", ContentModel::Css),
+        (5, "text", ContentModel::Text),
+    ] {
+        let id = ContentModelId::from(*id);
+        assert_eq!(id.as_str(), Some(*name));
+        assert_eq!(id.content_model().as_ref(), Some(model));
     }
+    assert_eq!(ContentModelId::from(0).as_str(), None);
+    assert_eq!(ContentModelId::from(1000).content_model(), None);
+    assert_eq!(
+        ContentModelId::from_sql(b"1,"),
+        Ok((&b","[..], ContentModelId(1)))
+    );
 }
 
-/// Represents the
-/// [`pr_level`](https://www.mediawiki.org/wiki/Manual:Page_restrictions_table#pr_level)
-/// field of the `page_restrictions` table, the group that is allowed
-/// to perform the action.
+string_enum! {
+    /// Represents the
+    /// [`img_media_type`](https://www.mediawiki.org/wiki/Manual:Image_table#img_media_type)
+    /// field of the `image` table.
+    MediaType {
+        Unknown => "UNKNOWN",
+        Bitmap => "BITMAP",
+        Drawing => "DRAWING",
+        Audio => "AUDIO",
+        Video => "VIDEO",
+        Multimedia => "MULTIMEDIA",
+        Office => "OFFICE",
+        Text => "TEXT",
+        Executable => "EXECUTABLE",
+        Archive => "ARCHIVE",
+        ThreeDimensional => "3D",
+    }
+}
+
+string_enum! {
+    /// Represents the
+    /// [`img_major_mime`](https://www.mediawiki.org/wiki/Manual:Image_table#img_major_mime)
+    /// field of the `image` table.
+    MajorMime {
+        Unknown => "unknown",
+        Application => "application",
+        Audio => "audio",
+        Image => "image",
+        Text => "text",
+        Video => "video",
+        Message => "message",
+        Model => "model",
+        Multipart => "multipart",
+    }
+}
+
+/**
+An enum like [`MediaType`] or [`MajorMime`] parsed without regard to ASCII case,
+so that `'bitmap'` is parsed as [`MediaType::Bitmap`] rather than [`MediaType::Other`],
+for dumps whose values don't have the usual case.
+The enum still converts to the usual string.
+
+The implementations for the enums themselves only match the usual case,
+so this must be opted into by using it as the type of a field.
+*/
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[cfg_attr(
     feature = "serialization",
     derive(Serialize, Deserialize),
-    serde(from = "&'a str", into = "&'a str")
+    serde(transparent)
 )]
-pub enum ProtectionLevel<'a> {
-    Autoconfirmed,
-    ExtendedConfirmed,
-    Sysop,
-    TemplateEditor,
-    EditProtected,
-    EditSemiProtected,
-    /// The result of parsing the empty string after the `=` in `'move=:edit='`.
-    None,
-    Other(&'a str),
-}
-
-impl<'a> From<&'a str> for ProtectionLevel<'a> {
-    fn from(s: &'a str) -> Self {
-        use ProtectionLevel::*;
-        match s {
-            "autoconfirmed" => Autoconfirmed,
-            "extendedconfirmed" => ExtendedConfirmed,
-            "templateeditor" => TemplateEditor,
-            "sysop" => Sysop,
-            "editprotected" => EditProtected,
-            "editsemiprotected" => EditSemiProtected,
-            "" => None,
-            _ => Other(s),
-        }
+pub struct CaseInsensitive<T>(pub T);
+
+impl<T> CaseInsensitive<T> {
+    pub fn into_inner(self) -> T {
+        self.0
     }
 }
 
-impl<'a> From<ProtectionLevel<'a>> for &'a str {
-    fn from(p: ProtectionLevel<'a>) -> &'a str {
-        use ProtectionLevel::*;
-        match p {
-            Autoconfirmed => "autoconfirmed",
-            ExtendedConfirmed => "extendedconfirmed",
-            TemplateEditor => "templateeditor",
-            Sysop => "sysop",
-            EditProtected => "editprotected",
-            EditSemiProtected => "editsemiprotected",
-            None => "",
-            Other(s) => s,
-        }
+#[test]
+fn test_case_insensitive() {
+    assert_eq!(
+        <CaseInsensitive<MediaType>>::from_sql(b"'bitmap'"),
+        Ok((B(""), CaseInsensitive(MediaType::Bitmap)))
+    );
+    assert_eq!(
+        <CaseInsensitive<MediaType>>::from_sql(b"'3d'"),
+        Ok((B(""), CaseInsensitive(MediaType::ThreeDimensional)))
+    );
+    assert_eq!(
+        <CaseInsensitive<MajorMime>>::from_sql(b"'IMAGE'"),
+        Ok((B(""), CaseInsensitive(MajorMime::Image)))
+    );
+    assert_eq!(
+        <CaseInsensitive<MajorMime>>::from_sql(b"'Chemical'"),
+        Ok((B(""), CaseInsensitive(MajorMime::Other("Chemical".into()))))
+    );
+    assert_eq!(
+        MediaType::from_sql(b"'bitmap'"),
+        Ok((B(""), MediaType::Other("bitmap".into())))
+    );
+}
+
+#[cfg(feature = "serialization")]
+#[test]
+fn test_case_insensitive_serialization() {
+    let media_type = CaseInsensitive(MediaType::Bitmap);
+    assert_eq!(serde_json::to_string(&media_type).unwrap(), r#""BITMAP""#);
+    assert_eq!(
+        serde_json::from_str::<CaseInsensitive<MediaType>>(r#""BITMAP""#).unwrap(),
+        media_type
+    );
+}
+
+impl_to_owned_field_by_clone!(
+    Timestamp,
+    OptionalTimestamp,
+    Expiry,
+    DurationSecs,
+    YesNoBool
+);
+
+#[cfg(feature = "time")]
+impl_to_owned_field_by_clone!(TimestampTime);
+
+impl<'a> ToOwnedField for NullableStr<'a> {
+    type Owned = Option<String>;
+
+    fn to_owned_field(&self) -> Option<String> {
+        self.0.to_owned_field()
     }
 }
 
-impl<'a> FromSql<'a> for ProtectionLevel<'a> {
-    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
-        context(
-            "ProtectionLevel",
-            map(<&str>::from_sql, ProtectionLevel::from),
-        )(s)
+impl<T: ToOwnedField> ToOwnedField for NewlineDelimited<T> {
+    type Owned = NewlineDelimited<T::Owned>;
+
+    fn to_owned_field(&self) -> Self::Owned {
+        NewlineDelimited(self.0.to_owned_field())
     }
 }
 
-/// Represents the
-/// [`page_content_model`](https://www.mediawiki.org/wiki/Manual:Page_table#page_content_model)
-/// field of the `page` table.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
-#[cfg_attr(
-    feature = "serialization",
-    derive(Serialize, Deserialize),
-    serde(from = "&'a str", into = "&'a str")
-)]
-pub enum ContentModel<'a> {
-    Wikitext,
-    Scribunto,
-    Text,
-    Css,
-    SanitizedCss,
-    JavaScript,
-    Json,
-    #[cfg_attr(feature = "serialization", serde(borrow))]
-    Other(&'a str),
-}
-
-impl<'a> From<&'a str> for ContentModel<'a> {
-    fn from(s: &'a str) -> Self {
-        use ContentModel::*;
-        match s {
-            "wikitext" => Wikitext,
-            "Scribunto" => Scribunto,
-            "text" => Text,
-            "css" => Css,
-            "sanitized-css" => SanitizedCss,
-            "javascript" => JavaScript,
-            "json" => Json,
-            _ => Other(s),
+impl<'a> ToOwnedField for PageRestrictionsOld<'a> {
+    type Owned = PageRestrictionsOld<'static>;
+
+    fn to_owned_field(&self) -> Self::Owned {
+        PageRestrictionsOld(self.0.to_owned_field())
+    }
+}
+
+/**
+Serializes byte string fields with [`Serializer::serialize_bytes`](serde::Serializer::serialize_bytes),
+so that serializers can tell them apart from sequences of integers,
+and deserializes them from bytes or a sequence of integers.
+Used with `#[serde(with = "crate::field_types::byte_string")]`
+on fields of type `Vec<u8>`, `&[u8]`, and `Option<Vec<u8>>`.
+*/
+#[cfg(feature = "serialization")]
+pub(crate) mod byte_string {
+    use serde::{
+        de::{SeqAccess, Visitor},
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use std::fmt;
+
+    pub(crate) trait ByteString<'de>: Sized {
+        fn serialize_byte_string<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+        fn deserialize_byte_string<D: Deserializer<'de>>(deserializer: D)
+            -> Result<Self, D::Error>;
+    }
+
+    struct Bytes<'a>(&'a [u8]);
+
+    impl<'a> Serialize for Bytes<'a> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    struct ByteBuf(Vec<u8>);
+
+    struct ByteBufVisitor;
+
+    impl<'de> Visitor<'de> for ByteBufVisitor {
+        type Value = ByteBuf;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a byte string")
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Ok(ByteBuf(v.to_vec()))
+        }
+
+        fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+            Ok(ByteBuf(v))
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+            Ok(ByteBuf(bytes))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ByteBuf {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_byte_buf(ByteBufVisitor)
+        }
+    }
+
+    impl<'de> ByteString<'de> for Vec<u8> {
+        fn serialize_byte_string<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self)
+        }
+
+        fn deserialize_byte_string<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            ByteBuf::deserialize(deserializer).map(|ByteBuf(bytes)| bytes)
+        }
+    }
+
+    impl<'de> ByteString<'de> for &'de [u8] {
+        fn serialize_byte_string<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self)
+        }
+
+        fn deserialize_byte_string<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            <&[u8]>::deserialize(deserializer)
+        }
+    }
+
+    impl<'de> ByteString<'de> for Option<Vec<u8>> {
+        fn serialize_byte_string<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                Some(bytes) => serializer.serialize_some(&Bytes(bytes)),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        fn deserialize_byte_string<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            Option::<ByteBuf>::deserialize(deserializer)
+                .map(|bytes| bytes.map(|ByteBuf(bytes)| bytes))
         }
     }
+
+    pub(crate) fn serialize<'de, T: ByteString<'de>, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.serialize_byte_string(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, T: ByteString<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        T::deserialize_byte_string(deserializer)
+    }
+}
+
+/**
+Writes a field as a value in a line of tab-separated values,
+for the `to_tsv_line` method of the [`schemas`](crate::schemas),
+like [`CategoryLink::to_tsv_line`](crate::schemas::CategoryLink::to_tsv_line).
+
+Backslashes, tabs, line feeds, and carriage returns in strings are escaped as
+`\\`, `\t`, `\n`, and `\r`, and `NULL` is written as `\N`,
+as in the files read by MySQL's `LOAD DATA`.
+Byte strings that aren't valid UTF-8 are converted with [`String::from_utf8_lossy`],
+booleans are written as `1` or `0`, and the other types are written with [`Display`](std::fmt::Display)
+or as the string that they were parsed from.
+*/
+pub trait ToTsvField {
+    /// Writes the field to `out`.
+    fn write_tsv_field(&self, out: &mut String);
 }
 
-impl<'a> From<ContentModel<'a>> for &'a str {
-    fn from(c: ContentModel<'a>) -> Self {
-        use ContentModel::*;
+/// Pushes a string with backslashes, tabs, line feeds, and carriage returns escaped.
+fn push_tsv_escaped(out: &mut String, s: &str) {
+    for c in s.chars() {
         match c {
-            Wikitext => "wikitext",
-            Scribunto => "Scribunto",
-            Text => "text",
-            Css => "css",
-            SanitizedCss => "sanitized-css",
-            JavaScript => "javascript",
-            Json => "json",
-            Other(s) => s,
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
         }
     }
 }
 
-impl<'a> FromSql<'a> for ContentModel<'a> {
-    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
-        context("ContentModel", map(<&str>::from_sql, ContentModel::from))(s)
+/// Implements [`ToTsvField`] for types whose [`Display`](std::fmt::Display) implementation
+/// doesn't need to be escaped.
+macro_rules! impl_to_tsv_field_by_display {
+    ($($type_name:ty),+ $(,)?) => {
+        $(
+            impl ToTsvField for $type_name {
+                fn write_tsv_field(&self, out: &mut String) {
+                    use std::fmt::Write as _;
+                    write!(out, "{}", self).expect("writing to a String should succeed");
+                }
+            }
+        )+
+    };
+}
+
+impl_to_tsv_field_by_display!(
+    u8,
+    u16,
+    u32,
+    u64,
+    i8,
+    i16,
+    i32,
+    i64,
+    f32,
+    f64,
+    NotNan<f64>,
+    NaiveDate
+);
+
+impl ToTsvField for bool {
+    fn write_tsv_field(&self, out: &mut String) {
+        out.push(if *self { '1' } else { '0' });
     }
 }
 
-/// Represents the
-/// [`img_media_type`](https://www.mediawiki.org/wiki/Manual:Image_table#img_media_type)
-/// field of the `image` table.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
-#[cfg_attr(
-    feature = "serialization",
-    derive(Serialize, Deserialize),
-    serde(from = "&'a str", into = "&'a str")
-)]
-pub enum MediaType<'a> {
-    Unknown,
-    Bitmap,
-    Drawing,
-    Audio,
-    Video,
-    Multimedia,
-    Office,
-    Text,
-    Executable,
-    Archive,
-    ThreeDimensional,
-    #[cfg_attr(feature = "serialization", serde(borrow))]
-    Other(&'a str),
-}
-
-impl<'a> From<&'a str> for MediaType<'a> {
-    fn from(s: &'a str) -> Self {
-        use MediaType::*;
-        match s {
-            "UNKNOWN" => Unknown,
-            "BITMAP" => Bitmap,
-            "DRAWING" => Drawing,
-            "AUDIO" => Audio,
-            "VIDEO" => Video,
-            "MULTIMEDIA" => Multimedia,
-            "OFFICE" => Office,
-            "TEXT" => Text,
-            "EXECUTABLE" => Executable,
-            "ARCHIVE" => Archive,
-            "3D" => ThreeDimensional,
-            _ => Other(s),
-        }
+impl ToTsvField for str {
+    fn write_tsv_field(&self, out: &mut String) {
+        push_tsv_escaped(out, self);
     }
 }
 
-impl<'a> From<MediaType<'a>> for &'a str {
-    fn from(s: MediaType<'a>) -> Self {
-        use MediaType::*;
-        match s {
-            Unknown => "UNKNOWN",
-            Bitmap => "BITMAP",
-            Drawing => "DRAWING",
-            Audio => "AUDIO",
-            Video => "VIDEO",
-            Multimedia => "MULTIMEDIA",
-            Office => "OFFICE",
-            Text => "TEXT",
-            Executable => "EXECUTABLE",
-            Archive => "ARCHIVE",
-            ThreeDimensional => "3D",
-            Other(s) => s,
-        }
+impl ToTsvField for String {
+    fn write_tsv_field(&self, out: &mut String) {
+        push_tsv_escaped(out, self);
     }
 }
 
-impl<'a> FromSql<'a> for MediaType<'a> {
-    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
-        context("MediaType", map(<&str>::from_sql, MediaType::from))(s)
+impl ToTsvField for [u8] {
+    fn write_tsv_field(&self, out: &mut String) {
+        push_tsv_escaped(out, &String::from_utf8_lossy(self));
     }
 }
 
-/// Represents the
-/// [`img_major_mime`](https://www.mediawiki.org/wiki/Manual:Image_table#img_major_mime)
-/// field of the `image` table.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
-#[cfg_attr(
-    feature = "serialization",
-    derive(Serialize, Deserialize),
-    serde(from = "&'a str", into = "&'a str")
-)]
-pub enum MajorMime<'a> {
-    Unknown,
-    Application,
-    Audio,
-    Image,
-    Text,
-    Video,
-    Message,
-    Model,
-    Multipart,
-    #[cfg_attr(feature = "serialization", serde(borrow))]
-    Other(&'a str),
-}
-
-impl<'a> From<&'a str> for MajorMime<'a> {
-    fn from(s: &'a str) -> Self {
-        use MajorMime::*;
-        match s {
-            "unknown" => Unknown,
-            "application" => Application,
-            "audio" => Audio,
-            "image" => Image,
-            "text" => Text,
-            "video" => Video,
-            "message" => Message,
-            "model" => Model,
-            "multipart" => Multipart,
-            _ => Other(s),
+impl ToTsvField for Vec<u8> {
+    fn write_tsv_field(&self, out: &mut String) {
+        self.as_slice().write_tsv_field(out);
+    }
+}
+
+impl<T: ToTsvField + ?Sized> ToTsvField for &T {
+    fn write_tsv_field(&self, out: &mut String) {
+        (**self).write_tsv_field(out);
+    }
+}
+
+impl<T: ToTsvField> ToTsvField for Option<T> {
+    fn write_tsv_field(&self, out: &mut String) {
+        match self {
+            Some(value) => value.write_tsv_field(out),
+            None => out.push_str("\\N"),
         }
     }
 }
 
-impl<'a> From<MajorMime<'a>> for &'a str {
-    fn from(s: MajorMime<'a>) -> Self {
-        use MajorMime::*;
-        match s {
-            Unknown => "unknown",
-            Application => "application",
-            Audio => "audio",
-            Image => "image",
-            Text => "text",
-            Video => "video",
-            Message => "message",
-            Model => "model",
-            Multipart => "multipart",
-            Other(s) => s,
+impl ToTsvField for Timestamp {
+    fn write_tsv_field(&self, out: &mut String) {
+        self.0.write_tsv_field(out);
+    }
+}
+
+impl_to_tsv_field_by_display!(NaiveDateTime);
+
+impl ToTsvField for OptionalTimestamp {
+    fn write_tsv_field(&self, out: &mut String) {
+        self.0.write_tsv_field(out);
+    }
+}
+
+/// Written as `1` or `0`, like [`bool`].
+impl ToTsvField for YesNoBool {
+    fn write_tsv_field(&self, out: &mut String) {
+        self.0.write_tsv_field(out);
+    }
+}
+
+/// Written as the number of seconds.
+impl ToTsvField for DurationSecs {
+    fn write_tsv_field(&self, out: &mut String) {
+        self.0.as_secs().write_tsv_field(out);
+    }
+}
+
+#[cfg(feature = "time")]
+impl ToTsvField for TimestampTime {
+    fn write_tsv_field(&self, out: &mut String) {
+        use std::fmt::Write as _;
+        write!(
+            out,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year(),
+            u8::from(self.month()),
+            self.day(),
+            self.hour(),
+            self.minute(),
+            self.second()
+        )
+        .expect("writing to a String should succeed");
+    }
+}
+
+impl ToTsvField for Expiry {
+    fn write_tsv_field(&self, out: &mut String) {
+        out.push_str(&String::from(*self));
+    }
+}
+
+impl<'a> ToTsvField for NullableStr<'a> {
+    fn write_tsv_field(&self, out: &mut String) {
+        self.0.write_tsv_field(out);
+    }
+}
+
+/// Joins the values with `\n`, the escaped form of the line breaks between them.
+impl<T: ToTsvField> ToTsvField for NewlineDelimited<T> {
+    fn write_tsv_field(&self, out: &mut String) {
+        for (i, value) in self.0.iter().enumerate() {
+            if i > 0 {
+                out.push_str("\\n");
+            }
+            value.write_tsv_field(out);
         }
     }
 }
 
-impl<'a> FromSql<'a> for MajorMime<'a> {
-    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
-        context("MajorMime", map(<&str>::from_sql, MajorMime::from))(s)
+impl<'a> ToTsvField for PageRestrictionsOld<'a> {
+    fn write_tsv_field(&self, out: &mut String) {
+        push_tsv_escaped(out, &self.to_string());
     }
 }
 
+/// Implements [`ToTsvField`] for enums that can be converted to a borrowed string.
+macro_rules! impl_to_tsv_field_as_str {
+    ($($type_name:ident),+ $(,)?) => {
+        $(
+            impl<'a> ToTsvField for $type_name<'a> {
+                fn write_tsv_field(&self, out: &mut String) {
+                    push_tsv_escaped(out, self.as_str());
+                }
+            }
+        )+
+    };
+}
+
+impl_to_tsv_field_as_str!(
+    PageType,
+    PageAction,
+    ProtectionLevel,
+    ContentModel,
+    MediaType,
+    MajorMime
+);
+
+#[test]
+fn test_to_tsv_field() {
+    let tsv = |field: &dyn ToTsvField| {
+        let mut out = String::new();
+        field.write_tsv_field(&mut out);
+        out
+    };
+    assert_eq!(tsv(&"a\tb\nc\\d\re"), r"a\tb\nc\\d\re");
+    assert_eq!(tsv(&None::<u32>), r"\N");
+    assert_eq!(tsv(&Some(true)), "1");
+    assert_eq!(tsv(&b"\xffA".to_vec()), "\u{FFFD}A");
+    assert_eq!(tsv(&PageNamespace(-1)), "-1");
+    assert_eq!(tsv(&Expiry::Infinity), "infinity");
+}
+
 #[test]
 fn test_bool() {
     for (s, v) in &[(B("0"), false), (B("1"), true)] {
@@ -815,6 +1876,27 @@ fn test_numbers() {
     }
 }
 
+/// `page_random` and other float columns can be dumped in scientific notation.
+#[test]
+fn test_float_scientific_notation() {
+    for (s, f) in &[
+        (B("1.5e-7,"), 1.5e-7),
+        (B("1.5E-7,"), 1.5e-7),
+        (B("2e3,"), 2000.0),
+        (B("6.02e+23,"), 6.02e23),
+        (B("0.000001,"), 1e-6),
+    ] {
+        assert_eq!(
+            <NotNan<f64>>::from_sql(s),
+            Ok((B(","), NotNan::new(*f).unwrap()))
+        );
+    }
+
+    let (_, negative_zero) = <NotNan<f64>>::from_sql(b"-0.0,").unwrap();
+    assert_eq!(negative_zero, NotNan::new(0.0).unwrap());
+    assert!(negative_zero.is_sign_negative());
+}
+
 #[test]
 fn test_string() {
     let strings = &[
@@ -831,42 +1913,3 @@ fn test_string() {
         assert_eq!(String::from_sql(s), Ok((B(""), (*unescaped).to_string())));
     }
 }
-
-#[cfg(feature = "serialization")]
-pub(crate) fn serialize_not_nan<S>(not_nan: &NotNan<f64>, serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    serializer.serialize_f64(not_nan.into_inner())
-}
-
-#[cfg(feature = "serialization")]
-pub(crate) fn deserialize_not_nan<'de, D>(deserializer: D) -> Result<NotNan<f64>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    NotNan::new(f64::deserialize(deserializer)?).map_err(serde::de::Error::custom)
-}
-
-#[cfg(feature = "serialization")]
-pub(crate) fn serialize_option_not_nan<S>(
-    not_nan: &Option<NotNan<f64>>,
-    serializer: S,
-) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    not_nan.map(|n| n.into_inner()).serialize(serializer)
-}
-
-#[cfg(feature = "serialization")]
-pub(crate) fn deserialize_option_not_nan<'de, D>(
-    deserializer: D,
-) -> Result<Option<NotNan<f64>>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    <Option<f64>>::deserialize(deserializer)?
-        .map(|v| NotNan::new(v).map_err(serde::de::Error::custom))
-        .transpose()
-}
@@ -2,18 +2,24 @@
 Defines the [`FromSql`] trait and implements it for external types.
 */
 
-use bstr::B;
+use bstr::{BStr, BString, B};
+use chrono::NaiveDate;
 use either::Either;
 use nom::{
     branch::alt,
     bytes::streaming::{escaped_transform, is_not, tag},
-    character::streaming::{char, digit1, one_of},
-    combinator::{map, map_res, opt, recognize},
+    character::streaming::{char, digit1, hex_digit1, one_of},
+    combinator::{map, map_opt, map_res, opt, recognize, value},
     error::context,
-    number::streaming::recognize_float,
+    multi::fold_many0,
+    number::streaming::{be_u8, recognize_float},
     sequence::{preceded, terminated, tuple},
 };
 use ordered_float::NotNan;
+use std::{
+    convert::TryFrom,
+    num::{NonZeroU32, NonZeroU64},
+};
 
 pub type IResult<'a, T> = nom::IResult<&'a [u8], T, crate::error::Error<'a>>;
 
@@ -25,6 +31,19 @@ Used by [`schemas::FromSqlTuple`][crate::FromSqlTuple].
 */
 pub trait FromSql<'a>: Sized {
     fn from_sql(s: &'a [u8]) -> IResult<'a, Self>;
+
+    /**
+    Like [`from_sql`](Self::from_sql), but unescapes string literals according to `dialect`.
+    Used by [`FromSqlTuple::from_sql_tuple_with_dialect`](crate::FromSqlTuple::from_sql_tuple_with_dialect).
+
+    The default ignores the dialect. Types that are parsed from unescaped string literals,
+    like [`Vec<u8>`] and [`String`], and types that contain other types, like [`Option`],
+    override it to pass the dialect on.
+    */
+    fn from_sql_with_dialect(s: &'a [u8], dialect: &SqlDialect) -> IResult<'a, Self> {
+        let _ = dialect;
+        Self::from_sql(s)
+    }
 }
 
 /// Parses a [`bool`] from `1` or `0`.
@@ -84,6 +103,18 @@ unsigned_int!(u16);
 unsigned_int!(u32);
 unsigned_int!(u64);
 
+macro_rules! nonzero_int {
+    ($t:ident) => {
+        number_impl! {
+            #[doc = concat!("Parses a [`", stringify!($t), "`], such as a primary key, and fails if it is `0`.")]
+            $t { recognize(digit1) }
+        }
+    };
+}
+
+nonzero_int!(NonZeroU32);
+nonzero_int!(NonZeroU64);
+
 macro_rules! signed_int {
     ($t:ident) => {
         number_impl! { $t { recognize(tuple((opt(char('-')), digit1))) } }
@@ -120,7 +151,21 @@ macro_rules! float {
 float!(f32);
 float!(f64);
 
+#[cfg(feature = "rust_decimal")]
+number_impl! {
+    /// Parses a `DECIMAL` literal, an integer with an optional fractional part,
+    /// as a [`Decimal`](rust_decimal::Decimal) without loss of precision.
+    rust_decimal::Decimal {
+        recognize(tuple((opt(char('-')), digit1, opt(tuple((char('.'), digit1))))))
+    }
+}
+
 /// Used for byte strings that have no escape sequences.
+///
+/// Because the byte string is borrowed from the input, escape sequences
+/// can't be unescaped, so a backslash causes an error rather than a
+/// truncated or still-escaped value. Use [`Vec<u8>`] or [`String`]
+/// for fields that may contain escape sequences.
 impl<'a> FromSql<'a> for &'a [u8] {
     fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
         context(
@@ -128,7 +173,7 @@ impl<'a> FromSql<'a> for &'a [u8] {
             preceded(
                 tag("'"),
                 terminated(
-                    map(opt(is_not(B("'"))), |opt| opt.unwrap_or_else(|| B(""))),
+                    map(opt(is_not(B("'\\"))), |opt| opt.unwrap_or_else(|| B(""))),
                     tag("'"),
                 ),
             ),
@@ -148,50 +193,350 @@ impl<'a> FromSql<'a> for &'a str {
     }
 }
 
+/// Parses a date in the format `'yyyymmdd'` or `'yyyy-mm-dd'`.
+impl<'a> FromSql<'a> for NaiveDate {
+    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+        context(
+            "date in yyyymmdd or yyyy-mm-dd format",
+            map_res(<&str>::from_sql, |s| {
+                NaiveDate::parse_from_str(s, if s.len() == 8 { "%Y%m%d" } else { "%Y-%m-%d" })
+            }),
+        )(s)
+    }
+}
+
 /// Use this for string types that require unescaping and are guaranteed
 /// to be valid UTF-8, like page titles.
 impl<'a> FromSql<'a> for String {
     fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
-        context("string", map_res(<Vec<u8>>::from_sql, String::from_utf8))(s)
+        Self::from_sql_with_dialect(s, &SqlDialect::MYSQL)
+    }
+
+    fn from_sql_with_dialect(s: &'a [u8], dialect: &SqlDialect) -> IResult<'a, Self> {
+        context(
+            "string",
+            map_res(
+                |s| <Vec<u8>>::from_sql_with_dialect(s, dialect),
+                String::from_utf8,
+            ),
+        )(s)
+    }
+}
+
+/// Stores strings of up to 23 bytes inline without allocating,
+/// which saves memory for short values like user groups or language codes.
+/// Strings without escape sequences are not copied before being converted.
+#[cfg(feature = "smol_str")]
+#[cfg_attr(docsrs, doc(cfg(feature = "smol_str")))]
+impl<'a> FromSql<'a> for smol_str::SmolStr {
+    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+        Self::from_sql_with_dialect(s, &SqlDialect::MYSQL)
+    }
+
+    fn from_sql_with_dialect(s: &'a [u8], dialect: &SqlDialect) -> IResult<'a, Self> {
+        context(
+            "string",
+            alt((
+                map(<&str>::from_sql, smol_str::SmolStr::new),
+                map(
+                    |s| String::from_sql_with_dialect(s, dialect),
+                    smol_str::SmolStr::from,
+                ),
+            )),
+        )(s)
+    }
+}
+
+/**
+The escape sequences recognized in string literals, for dumps that don't escape strings
+the way `mysqldump` does, such as re-exports by other programs.
+Each escape sequence is a backslash followed by a byte, which is replaced by another byte.
+
+The [`Default`] is the set of escape sequences written by `mysqldump`:
+`\0`, `\b`, `\t`, `\n`, `\r`, `\Z`, `\\`, `\'`, and `\"`.
+Pass a dialect to [`iterate_sql_insertions_with_dialect`](crate::iterate_sql_insertions_with_dialect)
+to use it when parsing the [`Vec<u8>`] and [`String`] fields of rows, and the types based on them,
+or to [`FromSql::from_sql_with_dialect`] to parse a single value.
+
+```
+use parse_mediawiki_sql::SqlDialect;
+let dialect = SqlDialect::default()
+    .with_escape(b'a', 0x07)
+    .without_escape(b'Z');
+```
+*/
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct SqlDialect {
+    escapes: [Option<u8>; 256],
+}
+
+impl SqlDialect {
+    /// The escape sequences written by `mysqldump`.
+    pub(crate) const MYSQL: SqlDialect = SqlDialect {
+        escapes: [None; 256],
+    }
+    .with_escape(b'0', b'\0')
+    .with_escape(b'b', 0x08)
+    .with_escape(b't', b'\t')
+    .with_escape(b'n', b'\n')
+    .with_escape(b'r', b'\r')
+    .with_escape(b'Z', 0x1A)
+    .with_escape(b'\\', b'\\')
+    .with_escape(b'\'', b'\'')
+    .with_escape(b'"', b'"');
+
+    /// Adds the escape sequence consisting of a backslash followed by `escaped`,
+    /// which is replaced by `replacement`, or changes its replacement.
+    pub const fn with_escape(mut self, escaped: u8, replacement: u8) -> Self {
+        self.escapes[escaped as usize] = Some(replacement);
+        self
+    }
+
+    /// Removes the escape sequence consisting of a backslash followed by `escaped`,
+    /// so that it causes an error.
+    pub const fn without_escape(mut self, escaped: u8) -> Self {
+        self.escapes[escaped as usize] = None;
+        self
+    }
+
+    /// Returns the byte that replaces the escape sequence consisting of a backslash
+    /// followed by `escaped`, if it is recognized.
+    pub const fn escape(&self, escaped: u8) -> Option<u8> {
+        self.escapes[escaped as usize]
+    }
+}
+
+impl Default for SqlDialect {
+    fn default() -> Self {
+        SqlDialect::MYSQL
+    }
+}
+
+/// Shows the escape sequences as a map from the escaped bytes to their replacements,
+/// both shown as the `char`s with the same values.
+impl std::fmt::Debug for SqlDialect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries((0..=u8::MAX).filter_map(|escaped| {
+                self.escape(escaped)
+                    .map(|replacement| (char::from(escaped), char::from(replacement)))
+            }))
+            .finish()
     }
 }
 
+/// Every byte, so that an escape sequence can be replaced by a slice that outlives the input.
+static BYTES: [u8; 256] = {
+    let mut bytes = [0; 256];
+    let mut i = 0;
+    while i < bytes.len() {
+        bytes[i] = i as u8;
+        i += 1;
+    }
+    bytes
+};
+
+fn byte_string<'a>(dialect: &SqlDialect, s: &'a [u8]) -> IResult<'a, Vec<u8>> {
+    context(
+        "byte string",
+        preceded(
+            tag("'"),
+            terminated(
+                map(
+                    opt(escaped_transform(
+                        is_not(B("\\\"'")),
+                        '\\',
+                        map_opt(be_u8, |escaped| {
+                            let replacement = usize::from(dialect.escape(escaped)?);
+                            Some(&BYTES[replacement..=replacement])
+                        }),
+                    )),
+                    |opt| opt.unwrap_or_default(),
+                ),
+                tag("'"),
+            ),
+        ),
+    )(s)
+}
+
 /// Used for "strings" that sometimes contain invalid UTF-8, like the
 /// `cl_sortkey` field in the `categorylinks` table, which is truncated to 230
 /// bits, sometimes in the middle of a UTF-8 sequence.
+///
+/// Escape sequences are unescaped as `mysqldump` writes them,
+/// or according to the [`SqlDialect`] passed to [`from_sql_with_dialect`](FromSql::from_sql_with_dialect).
 impl<'a> FromSql<'a> for Vec<u8> {
     fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+        byte_string(&SqlDialect::MYSQL, s)
+    }
+
+    fn from_sql_with_dialect(s: &'a [u8], dialect: &SqlDialect) -> IResult<'a, Self> {
+        byte_string(dialect, s)
+    }
+}
+
+#[test]
+fn test_sql_dialect() {
+    let bell = SqlDialect::default().with_escape(b'a', 0x07);
+    assert!(<Vec<u8>>::from_sql(br"'\a',").is_err());
+    assert_eq!(
+        <Vec<u8>>::from_sql_with_dialect(br"'ding\a\n',", &bell),
+        Ok((B(","), b"ding\x07\n".to_vec()))
+    );
+    assert_eq!(
+        String::from_sql_with_dialect(br"'\a',", &bell),
+        Ok((B(","), "\u{7}".to_string()))
+    );
+    assert_eq!(
+        <Option<(u32, String)>>::from_sql_with_dialect(br"1,'\a',", &bell),
+        Ok((B(","), Some((1, "\u{7}".to_string()))))
+    );
+
+    let no_z = SqlDialect::default().without_escape(b'Z');
+    assert!(<Vec<u8>>::from_sql_with_dialect(br"'\Z',", &no_z).is_err());
+    assert_eq!(
+        <Vec<u8>>::from_sql(br#"'\Z\0\b\t\r\\\'\"',"#),
+        Ok((B(","), b"\x1A\0\x08\t\r\\'\"".to_vec()))
+    );
+}
+
+/// Used for byte strings that have no escape sequences and are usually,
+/// but not always, valid UTF-8.
+impl<'a> FromSql<'a> for &'a BStr {
+    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+        map(<&[u8]>::from_sql, <&BStr>::from)(s)
+    }
+}
+
+/// Used for byte strings that may contain escape sequences and are usually,
+/// but not always, valid UTF-8.
+impl<'a> FromSql<'a> for BString {
+    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+        Self::from_sql_with_dialect(s, &SqlDialect::MYSQL)
+    }
+
+    fn from_sql_with_dialect(s: &'a [u8], dialect: &SqlDialect) -> IResult<'a, Self> {
+        map(
+            |s| <Vec<u8>>::from_sql_with_dialect(s, dialect),
+            BString::from,
+        )(s)
+    }
+}
+
+/// Used for fixed-length binary fields, like a SHA-1 hash stored as 20 bytes
+/// rather than as a base-36 string. Fails if the unescaped byte string
+/// does not have a length of exactly `N`.
+impl<'a, const N: usize> FromSql<'a> for [u8; N] {
+    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+        Self::from_sql_with_dialect(s, &SqlDialect::MYSQL)
+    }
+
+    fn from_sql_with_dialect(s: &'a [u8], dialect: &SqlDialect) -> IResult<'a, Self> {
         context(
-            "byte string",
-            preceded(
-                tag("'"),
-                terminated(
-                    map(
-                        opt(escaped_transform(
-                            is_not(B("\\\"'")),
-                            '\\',
-                            map(one_of(B(r#"0btnrZ\'""#)), |b| match b {
-                                '0' => B("\0"),
-                                'b' => b"\x08",
-                                't' => b"\t",
-                                'n' => b"\n",
-                                'r' => b"\r",
-                                'Z' => b"\x1A",
-                                '\\' => b"\\",
-                                '\'' => b"'",
-                                '"' => b"\"",
-                                _ => unreachable!(),
-                            }),
-                        )),
-                        |opt| opt.unwrap_or_default(),
-                    ),
+            "fixed-length byte string",
+            map_res(
+                |s| <Vec<u8>>::from_sql_with_dialect(s, dialect),
+                <[u8; N]>::try_from,
+            ),
+        )(s)
+    }
+}
+
+/**
+A string from a dump created in the `NO_BACKSLASH_ESCAPES` SQL mode,
+in which a single quote in a string literal is escaped by doubling it (`''`)
+and backslashes have no special meaning.
+
+Wikimedia dumps use backslash escapes, which are parsed by the implementations
+for [`Vec<u8>`] and [`String`], so this must be opted into by using it as the type of a field.
+*/
+#[derive(Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct NoBackslashEscapes<T>(pub T);
+
+impl<T> NoBackslashEscapes<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<'a> FromSql<'a> for NoBackslashEscapes<Vec<u8>> {
+    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+        context(
+            "byte string with doubled quotes",
+            map(
+                preceded(
                     tag("'"),
+                    terminated(
+                        fold_many0(
+                            alt((is_not(B("'")), value(B("'"), tag("''")))),
+                            Vec::new,
+                            |mut bytes, segment| {
+                                bytes.extend_from_slice(segment);
+                                bytes
+                            },
+                        ),
+                        tag("'"),
+                    ),
                 ),
+                NoBackslashEscapes,
             ),
         )(s)
     }
 }
 
+impl<'a> FromSql<'a> for NoBackslashEscapes<String> {
+    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+        context(
+            "string with doubled quotes",
+            map_res(<NoBackslashEscapes<Vec<u8>>>::from_sql, |bytes| {
+                String::from_utf8(bytes.0).map(NoBackslashEscapes)
+            }),
+        )(s)
+    }
+}
+
+/**
+An unsigned integer written as an unquoted hexadecimal literal, like `0xFF`,
+which some custom installations of MediaWiki have in integer columns.
+
+The implementations for the unsigned integer types only parse decimal integers,
+so this must be opted into by using it as the type of a field.
+It only parses hexadecimal literals, so a field can't be ambiguous between the two.
+*/
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct HexInt<T>(pub T);
+
+impl<T> HexInt<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+macro_rules! hex_int {
+    ($($t:ident),+) => {
+        $(
+            impl<'a> FromSql<'a> for HexInt<$t> {
+                fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+                    context(
+                        concat!("hexadecimal number (", stringify!($t), ")"),
+                        map_res(
+                            preceded(alt((tag("0x"), tag("0X"))), hex_digit1),
+                            |digits: &[u8]| {
+                                let digits = std::str::from_utf8(digits).map_err(Either::Right)?;
+                                $t::from_str_radix(digits, 16)
+                                    .map(HexInt)
+                                    .map_err(Either::Left)
+                            },
+                        ),
+                    )(s)
+                }
+            }
+        )+
+    };
+}
+
+hex_int!(u8, u16, u32, u64);
+
 impl<'a> FromSql<'a> for () {
     fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
         context("unit type", map(tag("NULL"), |_| ()))(s)
@@ -203,12 +548,280 @@ where
     T: FromSql<'a>,
 {
     fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+        Self::from_sql_with_dialect(s, &SqlDialect::MYSQL)
+    }
+
+    fn from_sql_with_dialect(s: &'a [u8], dialect: &SqlDialect) -> IResult<'a, Self> {
         context(
             "optional type",
             alt((
                 context("“NULL”", map(<()>::from_sql, |_| None)),
-                map(T::from_sql, Some),
+                map(|s| T::from_sql_with_dialect(s, dialect), Some),
+            )),
+        )(s)
+    }
+}
+
+macro_rules! tuple_impl {
+    ($first:ident $(, $rest:ident)+) => {
+        /// Parses the elements of a tuple as comma-separated values with no surrounding delimiters,
+        /// like `1,2`, for instance consecutive fields in a row that belong together.
+        /// Values inside a quoted string, like `'1,2'`, are not split;
+        /// parse the string and split it instead.
+        impl<'a, $first, $($rest),+> FromSql<'a> for ($first, $($rest),+)
+        where
+            $first: FromSql<'a>,
+            $($rest: FromSql<'a>,)+
+        {
+            fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+                Self::from_sql_with_dialect(s, &SqlDialect::MYSQL)
+            }
+
+            fn from_sql_with_dialect(s: &'a [u8], dialect: &SqlDialect) -> IResult<'a, Self> {
+                context(
+                    "comma-separated values",
+                    tuple((
+                        |s| $first::from_sql_with_dialect(s, dialect),
+                        $(preceded(char(','), |s| $rest::from_sql_with_dialect(s, dialect)),)+
+                    )),
+                )(s)
+            }
+        }
+    };
+}
+
+tuple_impl!(A, B);
+tuple_impl!(A, B, C);
+
+/// Tries to parse `A`, and if that fails, `B`.
+/// Useful for columns whose type differs between dumps.
+impl<'a, A, B> FromSql<'a> for Either<A, B>
+where
+    A: FromSql<'a>,
+    B: FromSql<'a>,
+{
+    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+        Self::from_sql_with_dialect(s, &SqlDialect::MYSQL)
+    }
+
+    fn from_sql_with_dialect(s: &'a [u8], dialect: &SqlDialect) -> IResult<'a, Self> {
+        context(
+            "either type",
+            alt((
+                map(|s| A::from_sql_with_dialect(s, dialect), Either::Left),
+                map(|s| B::from_sql_with_dialect(s, dialect), Either::Right),
             )),
         )(s)
     }
 }
+
+#[cfg(feature = "rust_decimal")]
+#[test]
+fn test_decimal() {
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    // Add a comma to the end to avoid `nom::Err::Incomplete`.
+    for (s, decimal) in &[
+        (B("42,"), "42"),
+        (B("-7,"), "-7"),
+        (B("3.14159265358979323846,"), "3.14159265358979323846"),
+        (B("-0.001,"), "-0.001"),
+    ] {
+        assert_eq!(
+            Decimal::from_sql(s),
+            Ok((B(","), Decimal::from_str(decimal).unwrap()))
+        );
+    }
+}
+
+#[cfg(feature = "smol_str")]
+#[test]
+fn test_smol_str() {
+    use smol_str::SmolStr;
+
+    let (rest, group) = SmolStr::from_sql(b"'interface-admin',").unwrap();
+    assert_eq!((rest, group.as_str()), (&b","[..], "interface-admin"));
+    assert!(!group.is_heap_allocated());
+    let (_, escaped) = SmolStr::from_sql(br"'it\'s',").unwrap();
+    assert_eq!(escaped, "it's");
+    assert!(!escaped.is_heap_allocated());
+    let (_, long) = SmolStr::from_sql(b"'English_words_prefixed_with_un-',").unwrap();
+    assert_eq!(long, "English_words_prefixed_with_un-");
+    assert!(long.is_heap_allocated());
+}
+
+#[test]
+fn test_byte_array() {
+    let sha1 = br"'\0\Z\'\\\n\r\t 0123456789ab'";
+    assert_eq!(
+        <[u8; 20]>::from_sql(sha1),
+        Ok((B(""), *b"\0\x1A'\\\n\r\t 0123456789ab"))
+    );
+    assert!(<[u8; 20]>::from_sql(b"'0123456789abcdef'").is_err());
+}
+
+#[test]
+fn test_nonzero() {
+    assert_eq!(
+        NonZeroU32::from_sql(b"42,"),
+        Ok((&b","[..], NonZeroU32::new(42).unwrap()))
+    );
+    assert_eq!(
+        NonZeroU64::from_sql(b"5000000001,"),
+        Ok((&b","[..], NonZeroU64::new(5000000001).unwrap()))
+    );
+    for s in &[&b"0,"[..], b"'0',", b"00,"] {
+        assert!(NonZeroU32::from_sql(s).is_err());
+        assert!(NonZeroU64::from_sql(s).is_err());
+    }
+    assert_eq!(
+        std::mem::size_of::<Option<NonZeroU32>>(),
+        std::mem::size_of::<u32>()
+    );
+}
+
+#[test]
+fn test_option_not_nan() {
+    // Add a comma to the end to avoid `nom::Err::Incomplete`.
+    assert_eq!(
+        <Option<NotNan<f64>>>::from_sql(b"NULL,"),
+        Ok((&b","[..], None))
+    );
+    assert_eq!(
+        <Option<NotNan<f64>>>::from_sql(b"0.25,"),
+        Ok((&b","[..], Some(NotNan::new(0.25).unwrap())))
+    );
+    assert_eq!(
+        <Option<NotNan<f64>>>::from_sql(b"-1e3,"),
+        Ok((&b","[..], Some(NotNan::new(-1000.0).unwrap())))
+    );
+    assert!(<Option<NotNan<f64>>>::from_sql(b"'0.25',").is_err());
+
+    #[cfg(feature = "serialization")]
+    {
+        let values = [None, Some(NotNan::new(0.25).unwrap())];
+        let json = serde_json::to_string(&values).unwrap();
+        assert_eq!(json, "[null,0.25]");
+        assert_eq!(
+            serde_json::from_str::<[Option<NotNan<f64>>; 2]>(&json).unwrap(),
+            values
+        );
+    }
+}
+
+#[test]
+fn test_tuples() {
+    // Add a closing parenthesis to the end to avoid `nom::Err::Incomplete`.
+    assert_eq!(
+        <(i32, i32)>::from_sql(b"640,-480)"),
+        Ok((&b")"[..], (640, -480)))
+    );
+    assert_eq!(
+        <(u32, Option<i32>, String)>::from_sql(b"1,NULL,'a,b')"),
+        Ok((&b")"[..], (1, None, "a,b".to_string())))
+    );
+    assert!(<(i32, i32)>::from_sql(b"640)").is_err());
+    assert!(<(i32, i32)>::from_sql(b"'640,480')").is_err());
+}
+
+#[test]
+fn test_either() {
+    // Add a comma to the end to avoid `nom::Err::Incomplete`.
+    assert_eq!(
+        <Either<u32, String>>::from_sql(b"42,"),
+        Ok((&b","[..], Either::Left(42)))
+    );
+    assert_eq!(
+        <Either<u32, String>>::from_sql(b"'forty-two',"),
+        Ok((&b","[..], Either::Right("forty-two".to_string())))
+    );
+    assert_eq!(
+        <Either<u32, Option<String>>>::from_sql(b"NULL,"),
+        Ok((&b","[..], Either::Right(None)))
+    );
+    assert!(<Either<u32, String>>::from_sql(b"-1,").is_err());
+}
+
+#[test]
+fn test_byte_strings() {
+    let strings = &[
+        (B(r"'\''"), B(r"'")),
+        (br"'\\'", br"\"),
+        (br"'\n'", b"\n"),
+        (br"'string'", br"string"),
+        (br"'\0xff'", b"\0xff"),
+        (b"'\xff\xfe'", b"\xff\xfe"),
+    ];
+    for (s, unescaped) in strings {
+        assert_eq!(BString::from_sql(s), Ok((B(""), BString::from(*unescaped))));
+    }
+
+    for s in &[&b"''"[..], b"'string'", b"'\xff\xfe'"] {
+        assert_eq!(
+            <&BStr>::from_sql(s),
+            Ok((B(""), <&BStr>::from(&s[1..s.len() - 1])))
+        );
+    }
+}
+
+#[test]
+fn test_borrowed_byte_string_with_escapes() {
+    assert_eq!(<&[u8]>::from_sql(b"'ab',"), Ok((&b","[..], &b"ab"[..])));
+    assert_eq!(<&[u8]>::from_sql(b"'',"), Ok((&b","[..], &b""[..])));
+    for s in &[&br"'a\'b',"[..], br"'a\\b',", br"'\n',"] {
+        assert!(
+            matches!(<&[u8]>::from_sql(s), Err(nom::Err::Error(_))),
+            "{:?}",
+            <&BStr>::from(*s)
+        );
+        assert!(<&str>::from_sql(s).is_err());
+        assert!(<Vec<u8>>::from_sql(s).is_ok());
+    }
+}
+
+#[test]
+fn test_hex_int() {
+    // Add a comma to the end to avoid `nom::Err::Incomplete`.
+    assert_eq!(<HexInt<u8>>::from_sql(b"0xFF,"), Ok((B(","), HexInt(255))));
+    assert_eq!(<HexInt<u32>>::from_sql(b"0Xff,"), Ok((B(","), HexInt(255))));
+    assert_eq!(
+        <HexInt<u64>>::from_sql(b"0xDEADBEEF12,"),
+        Ok((B(","), HexInt(0xDE_ADBE_EF12)))
+    );
+    assert!(<HexInt<u8>>::from_sql(b"0x100,").is_err());
+    assert!(<HexInt<u32>>::from_sql(b"255,").is_err());
+    assert!(<HexInt<u32>>::from_sql(b"0x,").is_err());
+    // The decimal parser stops before the `x`.
+    assert_eq!(u32::from_sql(b"0xFF,"), Ok((B("xFF,"), 0)));
+}
+
+#[test]
+fn test_no_backslash_escapes() {
+    // Add a comma to the end to avoid `nom::Err::Incomplete`.
+    let strings = &[
+        (B("'it''s',"), "it's"),
+        (B("'''''',"), "''"),
+        (B("'',"), ""),
+        (B(r"'C:\Windows\n',"), r"C:\Windows\n"),
+    ];
+    for (s, unescaped) in strings {
+        assert_eq!(
+            <NoBackslashEscapes<String>>::from_sql(s),
+            Ok((B(","), NoBackslashEscapes((*unescaped).to_string())))
+        );
+        assert_eq!(
+            <NoBackslashEscapes<Vec<u8>>>::from_sql(s),
+            Ok((B(","), NoBackslashEscapes(unescaped.as_bytes().to_vec())))
+        );
+    }
+}
+
+#[test]
+fn test_date() {
+    let date = NaiveDate::from_ymd_opt(2021, 3, 9).unwrap();
+    for s in &[B("'20210309'"), B("'2021-03-09'")] {
+        assert_eq!(NaiveDate::from_sql(s), Ok((B(""), date)));
+    }
+    assert!(NaiveDate::from_sql(B("'2021-13-09'")).is_err());
+}
@@ -0,0 +1,159 @@
+/*!
+Interning of strings that are repeated many times in a dump,
+like [`CategoryLink::collation`](crate::schemas::CategoryLink::collation)
+or the group names in the `user_groups` table.
+
+An [`InternedStr`] can be used in place of a [`String`] in a struct that implements
+[`FromSqlTuple`](crate::FromSqlTuple). Equal values share one [`Arc<str>`] allocation,
+which saves memory when many rows are kept in memory at once.
+To intern strings from fields that have already been parsed, use an [`Interner`].
+*/
+
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+    ops::Deref,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use nom::{branch::alt, combinator::map, error::context};
+
+use crate::from_sql::{FromSql, IResult, SqlDialect};
+
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A set of strings, each of which is allocated only once.
+#[derive(Debug, Default, Clone)]
+pub struct Interner {
+    strings: HashSet<Arc<str>>,
+}
+
+impl Interner {
+    /// Returns the [`Arc<str>`] equal to `s`, allocating it if `s` hasn't been interned before.
+    pub fn intern(&mut self, s: &str) -> Arc<str> {
+        if let Some(interned) = self.strings.get(s) {
+            Arc::clone(interned)
+        } else {
+            let interned: Arc<str> = Arc::from(s);
+            self.strings.insert(Arc::clone(&interned));
+            interned
+        }
+    }
+
+    /// The number of distinct strings that have been interned.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Whether no strings have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+/// The interner used by [`InternedStr`].
+/// Its strings are never freed, so it should only be used for strings
+/// with a small number of distinct values.
+fn global_interner() -> &'static Mutex<Interner> {
+    static INTERNER: OnceLock<Mutex<Interner>> = OnceLock::new();
+    INTERNER.get_or_init(Default::default)
+}
+
+/**
+A string that is interned in a global [`Interner`] when it is parsed or created with [`InternedStr::new`],
+so that all equal `InternedStr`s point to the same allocation.
+
+Interned strings are never freed, so only use this for fields with few distinct values.
+*/
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct InternedStr(Arc<str>);
+
+impl InternedStr {
+    /// Interns the string in the global interner.
+    pub fn new(s: &str) -> Self {
+        InternedStr(
+            global_interner()
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .intern(s),
+        )
+    }
+
+    /// Returns the shared string.
+    pub fn into_inner(self) -> Arc<str> {
+        self.0
+    }
+}
+
+impl Deref for InternedStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for InternedStr {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for InternedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Borrows the string if it has no escape sequences and otherwise unescapes it before interning it.
+impl<'a> FromSql<'a> for InternedStr {
+    fn from_sql(s: &'a [u8]) -> IResult<'a, Self> {
+        Self::from_sql_with_dialect(s, &SqlDialect::MYSQL)
+    }
+
+    fn from_sql_with_dialect(s: &'a [u8], dialect: &SqlDialect) -> IResult<'a, Self> {
+        context(
+            "interned string",
+            alt((
+                map(<&str>::from_sql, InternedStr::new),
+                map(
+                    |s| String::from_sql_with_dialect(s, dialect),
+                    |s| InternedStr::new(&s),
+                ),
+            )),
+        )(s)
+    }
+}
+
+#[cfg(feature = "serialization")]
+impl Serialize for InternedStr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+#[cfg(feature = "serialization")]
+impl<'de> Deserialize<'de> for InternedStr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(InternedStr::new(&String::deserialize(deserializer)?))
+    }
+}
+
+#[test]
+fn test_interned_str() {
+    let (_, a) = InternedStr::from_sql(b"'uca-default-u-kn',").unwrap();
+    let (_, b) = InternedStr::from_sql(b"'uca-default-u-kn',").unwrap();
+    assert_eq!(&*a, "uca-default-u-kn");
+    assert!(Arc::ptr_eq(&a.0, &b.0));
+
+    let (_, escaped) = InternedStr::from_sql(br"'it\'s',").unwrap();
+    assert_eq!(&*escaped, "it's");
+    assert!(Arc::ptr_eq(&escaped.0, &InternedStr::new("it's").0));
+
+    let mut interner = Interner::default();
+    let c = interner.intern("sysop");
+    let d = interner.intern(&String::from("sysop"));
+    assert!(Arc::ptr_eq(&c, &d));
+    assert_eq!(interner.len(), 1);
+}
@@ -0,0 +1,475 @@
+/*!
+Converts rows into [`serde_json::Value`]s with control over how byte strings appear.
+
+Fields like [`CategoryLink::sortkey`](crate::schemas::CategoryLink::sortkey) are
+[`Vec<u8>`] because they may not be valid UTF-8, and the derived [`Serialize`]
+implementations turn them into arrays of numbers, which are bulky and hard to read.
+
+Byte strings and strings may also contain NUL characters, from the escape `\0`.
+Parsing keeps them, but they can be removed or escaped when converting rows
+by passing a [`NulPolicy`] to [`to_json_value_with_nuls`].
+*/
+
+use std::{borrow::Cow, io::Write};
+
+use base64::Engine as _;
+use serde::{ser, Serialize, Serializer};
+use serde_json::Value;
+
+/// How [`to_json_value`] represents byte strings.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum BytesEncoding {
+    /// A string, with invalid UTF-8 replaced by `U+FFFD REPLACEMENT CHARACTER`.
+    Utf8Lossy,
+    /// A string containing the standard base64 encoding of the bytes, with padding.
+    Base64,
+    /// An array of numbers, as produced by the derived [`Serialize`] implementations.
+    NumberArray,
+}
+
+/// What [`to_json_value_with_nuls`] does with NUL characters in strings and byte strings.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum NulPolicy {
+    /// Leave NULs as they are.
+    #[default]
+    Keep,
+    /// Remove NULs.
+    Strip,
+    /// Replace each NUL with the two characters `\0`, as in the SQL dump.
+    Escape,
+}
+
+impl NulPolicy {
+    fn apply(self, bytes: &mut Vec<u8>) {
+        if self == NulPolicy::Keep || !bytes.contains(&0) {
+            return;
+        }
+        let mut applied = Vec::with_capacity(bytes.len() + 1);
+        for &byte in bytes.iter() {
+            match byte {
+                0 if self == NulPolicy::Escape => applied.extend_from_slice(b"\\0"),
+                0 => {}
+                _ => applied.push(byte),
+            }
+        }
+        *bytes = applied;
+    }
+}
+
+/**
+Serializes a row, such as one of the [`schemas`](crate::schemas), into a [`Value`],
+representing its byte strings as specified by `bytes_as`.
+
+Byte strings are the values serialized with [`Serializer::serialize_bytes`],
+which the [`schemas`](crate::schemas) use for their byte string fields.
+Other arrays, including arrays of small integers, are left as they are.
+
+# Errors
+Fails if the row can't be serialized, as described in [`serde_json::to_value`].
+*/
+pub fn to_json_value<T: Serialize>(row: &T, bytes_as: BytesEncoding) -> serde_json::Result<Value> {
+    to_json_value_with_nuls(row, bytes_as, NulPolicy::Keep)
+}
+
+/**
+Like [`to_json_value`], but also handles NUL characters in strings and byte strings
+as specified by `nuls`, for exports to programs that can't handle them.
+Only values serialized as strings or byte strings are changed.
+
+# Errors
+Fails if the row can't be serialized, as described in [`serde_json::to_value`].
+*/
+pub fn to_json_value_with_nuls<T: Serialize>(
+    row: &T,
+    bytes_as: BytesEncoding,
+    nuls: NulPolicy,
+) -> serde_json::Result<Value> {
+    row.serialize(Encoder {
+        inner: serde_json::value::Serializer,
+        bytes_as,
+        nuls,
+    })
+}
+
+/// Wraps a [`Serializer`] or one of its compound serializers,
+/// encoding byte strings and handling NULs in everything serialized through it.
+struct Encoder<S> {
+    inner: S,
+    bytes_as: BytesEncoding,
+    nuls: NulPolicy,
+}
+
+/// A value to be serialized with the same settings as the [`Encoder`] that contains it.
+struct Encoded<'a, T: ?Sized> {
+    value: &'a T,
+    bytes_as: BytesEncoding,
+    nuls: NulPolicy,
+}
+
+impl<'a, T: Serialize + ?Sized> Serialize for Encoded<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(Encoder {
+            inner: serializer,
+            bytes_as: self.bytes_as,
+            nuls: self.nuls,
+        })
+    }
+}
+
+impl<S> Encoder<S> {
+    fn wrap<'a, T: ?Sized>(&self, value: &'a T) -> Encoded<'a, T> {
+        Encoded {
+            value,
+            bytes_as: self.bytes_as,
+            nuls: self.nuls,
+        }
+    }
+
+    fn compound<C, E>(self, start: impl FnOnce(S) -> Result<C, E>) -> Result<Encoder<C>, E> {
+        Ok(Encoder {
+            inner: start(self.inner)?,
+            bytes_as: self.bytes_as,
+            nuls: self.nuls,
+        })
+    }
+}
+
+macro_rules! forward {
+    ($($method:ident($($arg:ident: $type:ty),*)),+ $(,)?) => {
+        $(
+            fn $method(self, $($arg: $type),*) -> Result<Self::Ok, Self::Error> {
+                self.inner.$method($($arg),*)
+            }
+        )+
+    };
+}
+
+impl<S: Serializer> Serializer for Encoder<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Encoder<S::SerializeSeq>;
+    type SerializeTuple = Encoder<S::SerializeTuple>;
+    type SerializeTupleStruct = Encoder<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Encoder<S::SerializeTupleVariant>;
+    type SerializeMap = Encoder<S::SerializeMap>;
+    type SerializeStruct = Encoder<S::SerializeStruct>;
+    type SerializeStructVariant = Encoder<S::SerializeStructVariant>;
+
+    forward!(
+        serialize_bool(v: bool),
+        serialize_i8(v: i8),
+        serialize_i16(v: i16),
+        serialize_i32(v: i32),
+        serialize_i64(v: i64),
+        serialize_i128(v: i128),
+        serialize_u8(v: u8),
+        serialize_u16(v: u16),
+        serialize_u32(v: u32),
+        serialize_u64(v: u64),
+        serialize_u128(v: u128),
+        serialize_f32(v: f32),
+        serialize_f64(v: f64),
+        serialize_none(),
+        serialize_unit(),
+        serialize_unit_struct(name: &'static str),
+        serialize_unit_variant(name: &'static str, variant_index: u32, variant: &'static str),
+    );
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        if self.nuls == NulPolicy::Keep || !v.contains('\0') {
+            return self.inner.serialize_str(v);
+        }
+        let mut bytes = v.as_bytes().to_vec();
+        self.nuls.apply(&mut bytes);
+        self.inner
+            .serialize_str(std::str::from_utf8(&bytes).expect("only NULs were changed"))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        let mut bytes = Cow::Borrowed(v);
+        if self.nuls != NulPolicy::Keep && v.contains(&0) {
+            self.nuls.apply(bytes.to_mut());
+        }
+        match self.bytes_as {
+            BytesEncoding::Utf8Lossy => self.inner.serialize_str(&String::from_utf8_lossy(&bytes)),
+            BytesEncoding::Base64 => self
+                .inner
+                .serialize_str(&base64::engine::general_purpose::STANDARD.encode(&bytes)),
+            BytesEncoding::NumberArray => self.inner.serialize_bytes(&bytes),
+        }
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_some(&value)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_newtype_struct(name, &value)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        let value = self.wrap(value);
+        self.inner
+            .serialize_newtype_variant(name, variant_index, variant, &value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.compound(|inner| inner.serialize_seq(len))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.compound(|inner| inner.serialize_tuple(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.compound(|inner| inner.serialize_tuple_struct(name, len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.compound(|inner| inner.serialize_tuple_variant(name, variant_index, variant, len))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.compound(|inner| inner.serialize_map(len))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.compound(|inner| inner.serialize_struct(name, len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.compound(|inner| inner.serialize_struct_variant(name, variant_index, variant, len))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+macro_rules! impl_compound {
+    ($($trait:ident { $($method:ident($($key:ident: $key_type:ty,)?)),+ })+) => {
+        $(
+            impl<S: ser::$trait> ser::$trait for Encoder<S> {
+                type Ok = S::Ok;
+                type Error = S::Error;
+
+                $(
+                    fn $method<T: Serialize + ?Sized>(
+                        &mut self,
+                        $($key: $key_type,)?
+                        value: &T,
+                    ) -> Result<(), Self::Error> {
+                        let value = self.wrap(value);
+                        self.inner.$method($($key,)? &value)
+                    }
+                )+
+
+                fn end(self) -> Result<Self::Ok, Self::Error> {
+                    self.inner.end()
+                }
+            }
+        )+
+    };
+}
+
+impl_compound! {
+    SerializeSeq { serialize_element() }
+    SerializeTuple { serialize_element() }
+    SerializeTupleStruct { serialize_field() }
+    SerializeTupleVariant { serialize_field() }
+    SerializeMap { serialize_key(), serialize_value() }
+    SerializeStruct { serialize_field(key: &'static str,) }
+    SerializeStructVariant { serialize_field(key: &'static str,) }
+}
+
+/**
+Writes each row as a JSON object on its own line ([JSON Lines](https://jsonlines.org/)),
+with byte strings serialized as arrays of numbers. Returns the number of rows written.
+
+To encode byte strings differently, map the rows with [`to_json_value`] first.
+*/
+pub fn write_json_lines<W, T, I>(mut out: W, rows: I) -> serde_json::Result<usize>
+where
+    W: Write,
+    T: Serialize,
+    I: IntoIterator<Item = T>,
+{
+    let mut count = 0;
+    for row in rows {
+        serde_json::to_writer(&mut out, &row)?;
+        out.write_all(b"\n").map_err(serde_json::Error::io)?;
+        count += 1;
+    }
+    out.flush().map_err(serde_json::Error::io)?;
+    Ok(count)
+}
+
+#[test]
+fn test_write_json_lines() {
+    let mut out = Vec::new();
+    let rows = vec![
+        serde_json::json!({ "a": 1 }),
+        serde_json::json!({ "a": [2, 3] }),
+    ];
+    assert_eq!(write_json_lines(&mut out, &rows).unwrap(), 2);
+    assert_eq!(out, b"{\"a\":1}\n{\"a\":[2,3]}\n");
+}
+
+#[test]
+fn test_to_json_value() {
+    use crate::{schemas::CategoryLink, FromSqlTuple};
+    use serde_json::json;
+
+    let (_, link) = CategoryLink::from_sql_tuple(
+        b"(1,'Nouns','\xff\xfeA','2023-01-01 00:00:00','','uca-default-u-kn','page')",
+    )
+    .unwrap();
+    let expected = |sortkey: Value, sortkey_prefix: Value| {
+        json!({
+            "from": 1,
+            "to": "Nouns",
+            "sortkey": sortkey,
+            "timestamp": "2023-01-01T00:00:00",
+            "sortkey_prefix": sortkey_prefix,
+            "collation": "uca-default-u-kn",
+            "type": "page",
+        })
+    };
+    assert_eq!(
+        to_json_value(&link, BytesEncoding::Utf8Lossy).unwrap(),
+        expected(json!("\u{FFFD}\u{FFFD}A"), json!(""))
+    );
+    assert_eq!(
+        to_json_value(&link, BytesEncoding::Base64).unwrap(),
+        expected(json!("//5B"), json!(""))
+    );
+    assert_eq!(
+        to_json_value(&link, BytesEncoding::NumberArray).unwrap(),
+        expected(json!([255, 254, 65]), json!([]))
+    );
+}
+
+#[test]
+fn test_nul_policy() {
+    use crate::{schemas::CategoryLink, FromSqlTuple};
+    use serde_json::json;
+
+    let (_, link) = CategoryLink::from_sql_tuple(
+        br"(1,'Nouns','A\0B','2023-01-01 00:00:00','\0','uca-default-u-kn','page')",
+    )
+    .unwrap();
+    assert_eq!(link.sortkey, b"A\0B");
+    let fields = |nuls: NulPolicy, bytes_as: BytesEncoding| {
+        let value = to_json_value_with_nuls(&link, bytes_as, nuls).unwrap();
+        (value["sortkey"].clone(), value["sortkey_prefix"].clone())
+    };
+    assert_eq!(
+        fields(NulPolicy::Keep, BytesEncoding::Utf8Lossy),
+        (json!("A\u{0}B"), json!("\u{0}"))
+    );
+    assert_eq!(
+        fields(NulPolicy::Strip, BytesEncoding::Utf8Lossy),
+        (json!("AB"), json!(""))
+    );
+    assert_eq!(
+        fields(NulPolicy::Escape, BytesEncoding::Utf8Lossy),
+        (json!(r"A\0B"), json!(r"\0"))
+    );
+    assert_eq!(
+        fields(NulPolicy::Strip, BytesEncoding::NumberArray),
+        (json!([65, 66]), json!([]))
+    );
+    assert_eq!(
+        fields(NulPolicy::Escape, BytesEncoding::Base64),
+        (json!("QVwwQg=="), json!("XDA="))
+    );
+    assert_eq!(
+        to_json_value_with_nuls(
+            &json!({ "s": "a\u{0}" }),
+            BytesEncoding::Utf8Lossy,
+            NulPolicy::Strip
+        )
+        .unwrap(),
+        json!({ "s": "a" })
+    );
+}
+
+#[test]
+fn test_integer_arrays_are_not_bytes() {
+    use crate::field_types::NewlineDelimited;
+    use serde_json::json;
+
+    let row = (
+        NewlineDelimited(vec![12u32, 34]),
+        NewlineDelimited(Vec::<u32>::new()),
+        NewlineDelimited(vec![0u32, 1]),
+    );
+    for &bytes_as in &[
+        BytesEncoding::Utf8Lossy,
+        BytesEncoding::Base64,
+        BytesEncoding::NumberArray,
+    ] {
+        for &nuls in &[NulPolicy::Keep, NulPolicy::Strip, NulPolicy::Escape] {
+            assert_eq!(
+                to_json_value_with_nuls(&row, bytes_as, nuls).unwrap(),
+                json!([[12, 34], [], [0, 1]]),
+            );
+        }
+    }
+}
+
+#[test]
+fn test_byte_string_deserialization() {
+    use crate::{
+        schemas::{CategoryLink, CategoryLinkOwned},
+        FromSqlTuple,
+    };
+
+    let (_, link) = CategoryLink::from_sql_tuple(
+        b"(1,'Nouns','\xff\xfeA','2023-01-01 00:00:00','','uca-default-u-kn','page')",
+    )
+    .unwrap();
+    let json = serde_json::to_string(&link).unwrap();
+    assert!(json.contains(r#""sortkey":[255,254,65]"#));
+    assert_eq!(serde_json::from_str::<CategoryLink>(&json).unwrap(), link);
+    assert_eq!(
+        serde_json::from_str::<CategoryLinkOwned>(&json).unwrap(),
+        link.to_owned_row()
+    );
+}
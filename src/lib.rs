@@ -56,8 +56,7 @@ let redirects: Vec<(PageNamespace, PageTitle)> =
 # fn main() {}
 ```
 
-Only a mutable reference to the struct is iterable, so a `for`-loop
-must use `&mut` or `.into_iter()` to iterate over the struct:
+The struct can be iterated over directly in a `for`-loop:
 
 ```no_run
 # #[cfg(feature = "utils")]
@@ -69,7 +68,7 @@ must use `&mut` or `.into_iter()` to iterate over the struct:
 # };
 # let page_sql =
 #     unsafe { memory_map("page.sql")? };
-for Page { namespace, title, is_redirect, .. } in &mut iterate_sql_insertions(&page_sql) {
+for Page { namespace, title, is_redirect, .. } in iterate_sql_insertions(&page_sql) {
     if is_redirect {
         dbg!((namespace, title));
     }
@@ -79,6 +78,9 @@ for Page { namespace, title, is_redirect, .. } in &mut iterate_sql_insertions(&p
 # #[cfg(not(feature = "utils"))]
 # fn main() {}
 ```
+
+To check for errors afterwards with [`SqlInsertions::finish`],
+iterate over a mutable reference (`&mut iterate_sql_insertions(&page_sql)`) instead.
 */
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
@@ -86,19 +88,32 @@ for Page { namespace, title, is_redirect, .. } in &mut iterate_sql_insertions(&p
 use bstr::{ByteSlice, B};
 use nom::{
     branch::alt,
-    bytes::streaming::{tag, take_while},
-    character::streaming::multispace0,
-    combinator::{iterator, opt, recognize, ParserIterator},
-    sequence::{preceded, tuple},
+    bytes::streaming::{escaped, is_not, tag, take_while},
+    character::streaming::{anychar, char, multispace0},
+    combinator::{iterator, opt, recognize},
+    error::{context, ErrorKind, ParseError},
+    multi::many0_count,
+    sequence::{pair, preceded, tuple},
 };
+use std::{cell::RefCell, marker::PhantomData, rc::Rc};
 
 pub mod error;
 pub mod field_types;
 pub mod from_sql;
+pub mod prelude;
 pub mod schemas;
 
-pub use error::Error;
-pub use from_sql::IResult;
+pub use error::{Error, InputError};
+pub use from_sql::{IResult, SqlDialect};
+#[cfg(feature = "intern")]
+#[cfg_attr(docsrs, doc(cfg(feature = "intern")))]
+pub mod intern;
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub mod json;
+#[cfg(feature = "arrow")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arrow")))]
+pub mod record_batch;
 #[cfg(feature = "utils")]
 #[cfg_attr(docsrs, doc(cfg(feature = "utils")))]
 pub mod utils;
@@ -110,45 +125,1728 @@ Used by [`iterate_sql_insertions`].
 */
 pub trait FromSqlTuple<'input>: Sized {
     fn from_sql_tuple(s: &'input [u8]) -> IResult<'input, Self>;
+
+    /**
+    Like [`from_sql_tuple`](Self::from_sql_tuple), but unescapes string literals according to `dialect`,
+    by parsing the fields with [`FromSql::from_sql_with_dialect`](from_sql::FromSql::from_sql_with_dialect).
+    Used by [`iterate_sql_insertions_with_dialect`].
+
+    The default ignores the dialect.
+    */
+    fn from_sql_tuple_with_dialect(s: &'input [u8], dialect: &SqlDialect) -> IResult<'input, Self> {
+        let _ = dialect;
+        Self::from_sql_tuple(s)
+    }
 }
 
 /**
 The entry point of the crate. Takes a SQL dump of a MediaWiki database table as bytes
 and yields an iterator over structs representing rows in the table.
 
-The return value is iterable as a mutable reference,
+The return value is iterable by value or as a mutable reference,
 and when iterated it yields structs representing the database rows (`Row`).
 These rows are represented as tuples in the SQL code.
 The tuples are parsed using [`FromSqlTuple::from_sql_tuple`]
 and the fields in the tuples are parsed by [`FromSql::from_sql`](from_sql::FromSql::from_sql).
 
 See the [example][crate#example] in the documentation, and see [`schemas`] for the full list of possible `Row`s.
+
+# Checking for errors
+Iteration stops at the first row that fails to parse, and at the end of the `INSERT` statements.
+To tell these apart, call [`SqlInsertions::finish`] after iterating.
+It returns `Ok` with the rest of the input if iteration stopped where a row or the start
+of an `INSERT` statement was expected but not found, which is normally the `;`
+after the last `INSERT` statement; pass it to [`is_end_of_insertions`] to make sure.
+It returns `Err(nom::Err::Failure(_))` if a row was malformed,
+or `Err(nom::Err::Incomplete(_))` if the input ended in the middle of a row.
+Iterating by value consumes the struct, so iterate over `&mut` to be able to call `finish`.
+
+# Panics
+Panics if the input does not contain `INSERT INTO`. Use [`try_iterate_sql_insertions`]
+to get an error instead, which also reports input that begins with a byte order mark.
+*/
+pub fn iterate_sql_insertions<'input, Row>(sql: &'input [u8]) -> SqlInsertions<'input, Row>
+where
+    Row: FromSqlTuple<'input> + 'input,
+{
+    SqlInsertions {
+        input: skip_to_insertions(sql),
+        state: SqlInsertionsState::Running,
+        row: PhantomData,
+    }
+}
+
+/**
+Like [`iterate_sql_insertions`], but returns an error rather than panicking
+if the input doesn't contain `INSERT INTO`, and also if it begins with a byte order mark,
+as a dump that has been saved by a text editor on Windows may.
+A UTF-16 file would otherwise panic because `INSERT INTO` isn't found,
+and a UTF-8 byte order mark would be silently skipped along with the rest of the header.
+
+# Errors
+Returns an [`InputError`] describing the problem with the input.
+*/
+pub fn try_iterate_sql_insertions<'input, Row>(
+    sql: &'input [u8],
+) -> Result<SqlInsertions<'input, Row>, InputError>
+where
+    Row: FromSqlTuple<'input> + 'input,
+{
+    InputError::check_bom(sql)?;
+    let start = sql.find("INSERT INTO").ok_or(InputError::NoInsertions)?;
+    Ok(SqlInsertions {
+        input: &sql[start..],
+        state: SqlInsertionsState::Running,
+        row: PhantomData,
+    })
+}
+
+#[test]
+fn test_try_iterate_sql_insertions() {
+    use schemas::LinkTarget;
+
+    let sql = "INSERT INTO `linktarget` VALUES (1,0,'Foo');";
+    let rows: Vec<_> = try_iterate_sql_insertions::<LinkTarget>(sql.as_bytes())
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(rows.len(), 1);
+
+    let with_bom = format!("\u{FEFF}{}", sql);
+    let error = try_iterate_sql_insertions::<LinkTarget>(with_bom.as_bytes()).err();
+    assert_eq!(error, Some(InputError::Utf8Bom));
+    assert!(error.unwrap().to_string().contains("byte order mark"));
+
+    let utf16: Vec<u8> = "\u{FEFF}"
+        .encode_utf16()
+        .chain(sql.encode_utf16())
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    assert_eq!(
+        try_iterate_sql_insertions::<LinkTarget>(&utf16).err(),
+        Some(InputError::Utf16Bom { big_endian: false })
+    );
+    let utf16: Vec<u8> = "\u{FEFF}"
+        .encode_utf16()
+        .chain(sql.encode_utf16())
+        .flat_map(u16::to_be_bytes)
+        .collect();
+    assert_eq!(
+        try_iterate_sql_insertions::<LinkTarget>(&utf16).err(),
+        Some(InputError::Utf16Bom { big_endian: true })
+    );
+
+    assert_eq!(
+        try_iterate_sql_insertions::<LinkTarget>(b"-- MySQL dump").err(),
+        Some(InputError::NoInsertions)
+    );
+}
+
+/**
+Like [`iterate_sql_insertions`], but starts at the first row that begins at or after
+the byte offset `start`, so that a dump can be split into byte ranges that are parsed separately,
+for instance by different workers.
+
+Because `mysqldump` writes each `INSERT` statement on its own line and escapes line breaks
+in strings, the beginning of the line containing `start` is the beginning of a statement
+or a line outside of the statements. The statement is scanned from there,
+skipping over string literals, to find the first row at or after `start`.
+If there is none, iteration starts at the next statement.
+
+To divide a dump between workers, give each one a start offset and have it stop
+at the first row that begins at or after the next worker's start offset,
+which is at `sql.len() - rows.remaining().len()` before the row is parsed.
+
+# Panics
+Panics if the input does not contain `INSERT INTO`.
+*/
+pub fn iterate_sql_insertions_from_offset<'input, Row>(
+    sql: &'input [u8],
+    start: usize,
+) -> SqlInsertions<'input, Row>
+where
+    Row: FromSqlTuple<'input> + 'input,
+{
+    SqlInsertions {
+        input: skip_to_row_at_offset(sql, start),
+        state: SqlInsertionsState::Running,
+        row: PhantomData,
+    }
+}
+
+/**
+Iterates over the rows of the table named `table_name`, parsing each into the matching
+[`schemas::Row`] variant, for when the table is only known at runtime.
+Returns `None` if no schema parses the table.
+
+The `categorylinks`, `externallinks`, `imagelinks`, `pagelinks`, and `templatelinks` tables
+have had more than one layout. For these, the layout is detected from the `CREATE TABLE` statement,
+as by [`PageLinkLayout::detect`](schemas::PageLinkLayout::detect),
+and the current layout is used if it can't be detected.
+
+Errors can't be checked with [`SqlInsertions::finish`] after iterating,
+so use [`iterate_sql_insertions`] with the right schema for that.
+
+```
+use parse_mediawiki_sql::{iterate_any, schemas::Row};
+let sql = b"INSERT INTO `category` VALUES (5,'Nouns',3,0,0);";
+let rows: Vec<_> = iterate_any("category", sql).unwrap().collect();
+assert!(matches!(rows.as_slice(), [Row::Category(category)] if category.pages.into_inner() == 3));
+assert!(iterate_any("not_a_table", sql).is_none());
+```
+
+# Panics
+Panics if the input does not contain `INSERT INTO`, like [`iterate_sql_insertions`].
+*/
+pub fn iterate_any<'input>(
+    table_name: &str,
+    sql: &'input [u8],
+) -> Option<Box<dyn Iterator<Item = schemas::Row<'input>> + 'input>> {
+    use schemas::*;
+
+    fn rows<'input, T>(sql: &'input [u8]) -> Box<dyn Iterator<Item = Row<'input>> + 'input>
+    where
+        T: FromSqlTuple<'input> + Into<Row<'input>> + 'input,
+    {
+        Box::new(iterate_sql_insertions::<T>(sql).into_iter().map(Into::into))
+    }
+
+    Some(match table_name {
+        "babel" => rows::<Babel>(sql),
+        "bot_passwords" => rows::<BotPassword>(sql),
+        "category" => rows::<Category>(sql),
+        "categorylinks" => match CategoryLinkLayout::detect(sql) {
+            Some(CategoryLinkLayout::Legacy) => rows::<CategoryLinkLegacy>(sql),
+            _ => rows::<CategoryLink>(sql),
+        },
+        "change_tag" => rows::<ChangeTag>(sql),
+        "change_tag_def" => rows::<ChangeTagDefinition>(sql),
+        "externallinks" => match ExternalLinkLayout::detect(sql) {
+            Some(ExternalLinkLayout::Legacy) => rows::<ExternalLinkLegacy>(sql),
+            _ => rows::<ExternalLink>(sql),
+        },
+        "geo_tags" => rows::<GeoTag>(sql),
+        "image" => rows::<Image>(sql),
+        "imagelinks" => match ImageLinkLayout::detect(sql) {
+            Some(ImageLinkLayout::Legacy) => rows::<ImageLink>(sql),
+            _ => rows::<ImageLinkTarget>(sql),
+        },
+        "iwlinks" => rows::<InterwikiLink>(sql),
+        "job" => rows::<Job>(sql),
+        "l10n_cache" => rows::<L10nCache>(sql),
+        "langlinks" => rows::<LanguageLink>(sql),
+        "linktarget" => rows::<LinkTarget>(sql),
+        "page" => rows::<Page>(sql),
+        "page_props" => rows::<PageProperty>(sql),
+        "page_restrictions" => rows::<PageRestriction>(sql),
+        "pagelinks" => match PageLinkLayout::detect(sql) {
+            Some(PageLinkLayout::Legacy) => rows::<PageLinkLegacy>(sql),
+            Some(PageLinkLayout::VeryOld) => rows::<PageLinkVeryOld>(sql),
+            _ => rows::<PageLink>(sql),
+        },
+        "protected_titles" => rows::<ProtectedTitle>(sql),
+        "redirect" => rows::<Redirect>(sql),
+        "site_stats" => rows::<SiteStats>(sql),
+        "sites" => rows::<Site>(sql),
+        "templatelinks" => match TemplateLinkLayout::detect(sql) {
+            Some(TemplateLinkLayout::Legacy) => rows::<TemplateLinkLegacy>(sql),
+            _ => rows::<TemplateLink>(sql),
+        },
+        "uploadstash" => rows::<UploadStash>(sql),
+        "user_former_groups" => rows::<UserFormerGroupMembership>(sql),
+        "user_groups" => rows::<UserGroupMembership>(sql),
+        "wbc_entity_usage" => rows::<WikibaseClientEntityUsage>(sql),
+        "wbt_term_in_lang" => rows::<WbtTermInLang>(sql),
+        "wbt_text" => rows::<WbtText>(sql),
+        "wbt_text_in_lang" => rows::<WbtTextInLang>(sql),
+        "wbt_type" => rows::<WbtType>(sql),
+        _ => return None,
+    })
+}
+
+#[test]
+fn test_iterate_any() {
+    use field_types::{CategoryId, PageCount, PageTitle};
+    use schemas::{Category, CategoryLinkLegacy, L10nCache, Row};
+
+    let sql = b"INSERT INTO `category` VALUES (5,'Nouns',3,0,0),(6,'Verbs',2,1,0);";
+    let rows: Vec<_> = iterate_any("category", sql).unwrap().collect();
+    assert_eq!(
+        rows,
+        [
+            Row::Category(Category {
+                id: CategoryId(5),
+                title: PageTitle("Nouns".to_string()),
+                pages: PageCount(3),
+                subcats: PageCount(0),
+                files: PageCount(0),
+            }),
+            Row::Category(Category {
+                id: CategoryId(6),
+                title: PageTitle("Verbs".to_string()),
+                pages: PageCount(2),
+                subcats: PageCount(1),
+                files: PageCount(0),
+            }),
+        ]
+    );
+    assert!(iterate_any("not_a_table", sql).is_none());
+
+    let legacy_sql = b"CREATE TABLE `categorylinks` (
+  `cl_from` int(8) unsigned NOT NULL DEFAULT '0',
+  `cl_to` varchar(255) binary NOT NULL DEFAULT '',
+  `cl_sortkey` varchar(70) binary NOT NULL DEFAULT '',
+  `cl_timestamp` timestamp NOT NULL
+);
+INSERT INTO `categorylinks` VALUES (4107,'English_nouns','Dictionary','2008-03-14 18:32:07');";
+    assert!(matches!(
+        iterate_any("categorylinks", legacy_sql)
+            .unwrap()
+            .collect::<Vec<_>>()
+            .as_slice(),
+        [Row::CategoryLinkLegacy(CategoryLinkLegacy { from, .. })] if from.into_inner() == 4107
+    ));
+
+    let sql = b"INSERT INTO `l10n_cache` VALUES ('en','messages:mainpage','b:1;');";
+    assert!(matches!(
+        iterate_any("l10n_cache", sql)
+            .unwrap()
+            .collect::<Vec<_>>()
+            .as_slice(),
+        [Row::L10nCache(L10nCache { lang: "en", .. })]
+    ));
+}
+
+/// Returns the input starting at the comma before the first row at or after `start`,
+/// or at the beginning of the `INSERT` statement if the row is the first in the statement.
+fn skip_to_row_at_offset(sql: &[u8], start: usize) -> &[u8] {
+    const INSERT: &str = "INSERT INTO `";
+    let start = start.min(sql.len());
+    let line_start = sql[..start].rfind_byte(b'\n').map_or(0, |pos| pos + 1);
+    let line_end = sql[line_start..]
+        .find_byte(b'\n')
+        .map_or(sql.len(), |pos| line_start + pos);
+    let line = &sql[line_start..line_end];
+    if let (true, Some(values)) = (line.starts_with(INSERT.as_bytes()), line.find(" VALUES (")) {
+        let first_row = line_start + values + " VALUES ".len();
+        if start <= first_row {
+            return &sql[line_start..];
+        }
+        let (mut in_string, mut escaped, mut depth) = (false, false, 0usize);
+        for (pos, &byte) in sql.iter().enumerate().take(line_end).skip(first_row) {
+            if in_string {
+                match byte {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'\'' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match byte {
+                b'\'' => in_string = true,
+                b'(' => {
+                    if depth == 0 && pos >= start {
+                        // The comma before the row.
+                        return &sql[pos - 1..];
+                    }
+                    depth += 1;
+                }
+                b')' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+    match sql[line_end..].find(["\n", INSERT].concat()) {
+        Some(pos) => &sql[line_end + pos + 1..],
+        None => {
+            // Start at the `;` that ends the last statement, so that no rows are parsed.
+            let last_statement = sql
+                .rfind(["\n", INSERT].concat())
+                .map(|pos| pos + 1)
+                .or_else(|| sql.starts_with(INSERT.as_bytes()).then_some(0))
+                .expect("INSERT INTO statement");
+            let last_statement_end = sql[last_statement..]
+                .find_byte(b'\n')
+                .map_or(sql.len(), |pos| last_statement + pos);
+            let semicolon = sql[..last_statement_end]
+                .rfind_byte(b';')
+                .unwrap_or(last_statement_end);
+            &sql[semicolon.max(last_statement)..]
+        }
+    }
+}
+
+#[test]
+fn test_iterate_sql_insertions_from_offset() {
+    use crate::schemas::LinkTarget;
+    use std::fmt::Write as _;
+
+    let mut sql = String::from("-- MySQL dump\nCREATE TABLE `linktarget` (\n);\n");
+    let mut row_starts = Vec::new();
+    for statement in 0..5 {
+        sql.push_str("INSERT INTO `linktarget` VALUES ");
+        for i in 0..4 {
+            let id = statement * 4 + i + 1;
+            if i > 0 {
+                sql.push(',');
+            }
+            row_starts.push((sql.len(), id));
+            // Titles that look like the boundaries between rows must not be mistaken for them.
+            write!(sql, r"({},0,'A\'),({},0,\'B VALUES (')", id, id).unwrap();
+        }
+        sql.push_str(";\n");
+    }
+    sql.push_str("/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;\n");
+    let sql = sql.as_bytes();
+
+    for start in 0..=sql.len() + 1 {
+        let mut rows = iterate_sql_insertions_from_offset::<LinkTarget>(sql, start);
+        let ids: Vec<_> = (&mut rows).map(|row| row.id.into_inner()).collect();
+        let expected: Vec<_> = row_starts
+            .iter()
+            .filter(|&&(row_start, _)| row_start >= start)
+            .map(|&(_, id)| id)
+            .collect();
+        assert_eq!(ids, expected, "start: {}", start);
+        match rows.finish() {
+            Ok((remaining, ())) => assert!(is_end_of_insertions(remaining), "start: {}", start),
+            Err(e) => panic!("start: {}: {:?}", start, e),
+        }
+    }
+}
+
+/**
+The iterator returned by [`iterate_sql_insertions`].
+
+Like [`ParserIterator`](nom::combinator::ParserIterator), it implements [`Iterator`] as a mutable reference,
+so that [`finish`](Self::finish) can be called after iterating.
+It also implements [`IntoIterator`], so it can be used directly in a `for`-loop:
+
+```
+use parse_mediawiki_sql::{iterate_sql_insertions, schemas::Redirect};
+
+let sql = br"INSERT INTO `redirect` VALUES (1,0,'Target','',''),(2,10,'Other_target','','');
+/*!40000 ALTER TABLE `redirect` ENABLE KEYS */;
+";
+let mut titles = Vec::new();
+for Redirect { title, .. } in iterate_sql_insertions(sql) {
+    titles.push(title.0);
+}
+assert_eq!(titles, ["Target", "Other_target"]);
+```
+
+Unlike the return type of a function returning `impl Iterator`,
+it can be named, so it can be stored in a struct.
+
+To parse at most `n` rows, for instance to try out a program on a huge dump,
+use `(&mut rows).take(n)`. [`remaining`](Self::remaining) then returns the unparsed input.
+To guard against an accumulator growing too large, see `utils::fold_with_cap`.
+*/
+#[derive(Debug)]
+#[must_use = "implements `Iterator` as a mutable reference, and does nothing unless consumed"]
+pub struct SqlInsertions<'input, Row> {
+    input: &'input [u8],
+    state: SqlInsertionsState<'input>,
+    row: PhantomData<fn() -> Row>,
+}
+
+#[derive(Debug)]
+enum SqlInsertionsState<'input> {
+    Running,
+    Done,
+    Failed(nom::Err<Error<'input>>),
+}
+
+impl<'input, Row> SqlInsertions<'input, Row> {
+    /// The input that hasn't been parsed yet.
+    pub fn remaining(&self) -> &'input [u8] {
+        self.input
+    }
+
+    /**
+    Returns the rest of the input if iteration stopped without an error,
+    or the error that stopped it. See [`iterate_sql_insertions`] for the meaning of the results.
+    Has the same signature as [`ParserIterator::finish`](nom::combinator::ParserIterator::finish).
+    */
+    pub fn finish(self) -> IResult<'input, ()> {
+        match self.state {
+            SqlInsertionsState::Running | SqlInsertionsState::Done => Ok((self.input, ())),
+            SqlInsertionsState::Failed(e) => Err(e),
+        }
+    }
+
+    /**
+    Iterates over the rows paired with their [primary keys](schemas::PrimaryKey),
+    instead of destructuring each row to get the key:
+
+    ```
+    use parse_mediawiki_sql::{
+        field_types::{PageId, PageTitle},
+        iterate_sql_insertions,
+        schemas::Page,
+    };
+    use std::collections::HashMap;
+    let sql = br"INSERT INTO `page` VALUES (2,0,'A',0,0,0.5,'20230102030405',NULL,1,10,'wikitext',NULL);";
+    let pages: HashMap<PageId, Page> = iterate_sql_insertions(sql).with_keys().collect();
+    assert_eq!(pages[&PageId(2)].title, PageTitle("A".to_string()));
+    ```
+    */
+    pub fn with_keys(self) -> impl Iterator<Item = (Row::Key, Row)> + 'input
+    where
+        Row: FromSqlTuple<'input> + schemas::PrimaryKey + 'input,
+    {
+        self.into_iter().map(|row| (row.primary_key(), row))
+    }
+}
+
+impl<'input, Row> SqlInsertions<'input, Row> {
+    /// Parses the next row with `parse_tuple`, for the iterators that wrap this one.
+    fn next_with(
+        &mut self,
+        parse_tuple: impl FnMut(&'input [u8]) -> IResult<'input, Row>,
+    ) -> Option<Row> {
+        if !matches!(self.state, SqlInsertionsState::Running) {
+            return None;
+        }
+        match preceded(insertion_start, parse_tuple)(self.input) {
+            Ok((rest, row)) => {
+                self.input = rest;
+                Some(row)
+            }
+            Err(nom::Err::Error(_)) => {
+                self.state = SqlInsertionsState::Done;
+                None
+            }
+            Err(e) => {
+                self.state = SqlInsertionsState::Failed(e);
+                None
+            }
+        }
+    }
+}
+
+impl<'input, Row> Iterator for &mut SqlInsertions<'input, Row>
+where
+    Row: FromSqlTuple<'input> + 'input,
+{
+    type Item = Row;
+
+    fn next(&mut self) -> Option<Row> {
+        self.next_with(Row::from_sql_tuple)
+    }
+}
+
+impl<'input, Row> IntoIterator for SqlInsertions<'input, Row>
+where
+    Row: FromSqlTuple<'input> + 'input,
+{
+    type Item = Row;
+    type IntoIter = SqlInsertionsIntoIter<'input, Row>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        SqlInsertionsIntoIter(self)
+    }
+}
+
+/**
+The iterator returned by iterating over [`SqlInsertions`] by value.
+*/
+#[derive(Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct SqlInsertionsIntoIter<'input, Row>(SqlInsertions<'input, Row>);
+
+impl<'input, Row> SqlInsertionsIntoIter<'input, Row> {
+    /// Returns the underlying [`SqlInsertions`], for instance to call [`SqlInsertions::finish`].
+    pub fn into_inner(self) -> SqlInsertions<'input, Row> {
+        self.0
+    }
+}
+
+impl<'input, Row> Iterator for SqlInsertionsIntoIter<'input, Row>
+where
+    Row: FromSqlTuple<'input> + 'input,
+{
+    type Item = Row;
+
+    fn next(&mut self) -> Option<Row> {
+        (&mut self.0).next()
+    }
+}
+
+/**
+Like [`iterate_sql_insertions`], but calls `callback` after every `every` rows
+with the number of rows parsed so far and the number of bytes of `sql` consumed so far.
+
+Comparing the number of bytes consumed to the length of `sql` gives the approximate progress,
+which is useful for displaying a progress bar while parsing a large file.
+
+# Panics
+Panics if `every` is 0.
+*/
+pub fn iterate_sql_insertions_with_progress<'input, Row, C>(
+    sql: &'input [u8],
+    every: usize,
+    callback: C,
+) -> SqlInsertionsWithProgress<'input, Row, C>
+where
+    Row: FromSqlTuple<'input> + 'input,
+    C: FnMut(usize, usize),
+{
+    assert!(
+        every > 0,
+        "progress must be reported after at least one row"
+    );
+    SqlInsertionsWithProgress {
+        rows: iterate_sql_insertions(sql),
+        sql_len: sql.len(),
+        every,
+        count: 0,
+        callback,
+    }
+}
+
+/**
+The iterator returned by [`iterate_sql_insertions_with_progress`].
+
+Like [`SqlInsertions`], it implements [`Iterator`] as a mutable reference,
+so that [`finish`](Self::finish) can be called after iterating.
+*/
+#[must_use = "implements `Iterator` as a mutable reference, and does nothing unless consumed"]
+pub struct SqlInsertionsWithProgress<'input, Row, C> {
+    rows: SqlInsertions<'input, Row>,
+    sql_len: usize,
+    every: usize,
+    count: usize,
+    callback: C,
+}
+
+impl<'input, Row, C> SqlInsertionsWithProgress<'input, Row, C> {
+    /// The input that hasn't been parsed yet.
+    pub fn remaining(&self) -> &'input [u8] {
+        self.rows.remaining()
+    }
+
+    /// Returns the rest of the input or the error that stopped iteration,
+    /// like [`SqlInsertions::finish`].
+    pub fn finish(self) -> IResult<'input, ()> {
+        self.rows.finish()
+    }
+}
+
+/// Omits the callback, which usually doesn't implement [`Debug`](std::fmt::Debug).
+impl<'input, Row: std::fmt::Debug, C> std::fmt::Debug
+    for SqlInsertionsWithProgress<'input, Row, C>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqlInsertionsWithProgress")
+            .field("rows", &self.rows)
+            .field("sql_len", &self.sql_len)
+            .field("every", &self.every)
+            .field("count", &self.count)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'input, Row, C> Iterator for &mut SqlInsertionsWithProgress<'input, Row, C>
+where
+    Row: FromSqlTuple<'input> + 'input,
+    C: FnMut(usize, usize),
+{
+    type Item = Row;
+
+    fn next(&mut self) -> Option<Row> {
+        let SqlInsertionsWithProgress {
+            rows,
+            sql_len,
+            every,
+            count,
+            callback,
+        } = &mut **self;
+        rows.next_with(|tuple| {
+            let (rest, row) = Row::from_sql_tuple(tuple)?;
+            *count += 1;
+            if *count % *every == 0 {
+                callback(*count, *sql_len - rest.len());
+            }
+            Ok((rest, row))
+        })
+    }
+}
+
+/**
+Like [`iterate_sql_insertions`], but unescapes string literals according to `dialect`
+rather than the escape sequences written by `mysqldump`,
+for dumps produced by other programs.
+
+Each row is parsed with [`FromSqlTuple::from_sql_tuple_with_dialect`], which passes the dialect
+to the [`FromSql`](from_sql::FromSql) implementations for [`Vec<u8>`] and [`String`]
+and the types based on them.
+Strings that are parsed without unescaping, like [`&str`](str), are unaffected.
+
+```
+use parse_mediawiki_sql::{iterate_sql_insertions_with_dialect, schemas::Category, SqlDialect};
+let sql = br"INSERT INTO `category` VALUES (1,'Bell\a',0,0,0);";
+let dialect = SqlDialect::default().with_escape(b'a', 0x07);
+let categories: Vec<Category> = iterate_sql_insertions_with_dialect(sql, dialect).collect();
+assert_eq!(categories[0].title.0, "Bell\u{7}");
+```
+*/
+pub fn iterate_sql_insertions_with_dialect<'input, Row>(
+    sql: &'input [u8],
+    dialect: SqlDialect,
+) -> SqlInsertionsWithDialect<'input, Row>
+where
+    Row: FromSqlTuple<'input> + 'input,
+{
+    SqlInsertionsWithDialect {
+        rows: iterate_sql_insertions(sql),
+        dialect,
+    }
+}
+
+/**
+The iterator returned by [`iterate_sql_insertions_with_dialect`].
+
+Like [`SqlInsertions`], it implements [`Iterator`] as a mutable reference,
+so that [`finish`](Self::finish) can be called after iterating.
+*/
+#[derive(Debug)]
+#[must_use = "implements `Iterator` as a mutable reference, and does nothing unless consumed"]
+pub struct SqlInsertionsWithDialect<'input, Row> {
+    rows: SqlInsertions<'input, Row>,
+    dialect: SqlDialect,
+}
+
+impl<'input, Row> SqlInsertionsWithDialect<'input, Row> {
+    /// The input that hasn't been parsed yet.
+    pub fn remaining(&self) -> &'input [u8] {
+        self.rows.remaining()
+    }
+
+    /// Returns the rest of the input or the error that stopped iteration,
+    /// like [`SqlInsertions::finish`].
+    pub fn finish(self) -> IResult<'input, ()> {
+        self.rows.finish()
+    }
+}
+
+impl<'input, Row> Iterator for &mut SqlInsertionsWithDialect<'input, Row>
+where
+    Row: FromSqlTuple<'input> + 'input,
+{
+    type Item = Row;
+
+    fn next(&mut self) -> Option<Row> {
+        let SqlInsertionsWithDialect { rows, dialect } = &mut **self;
+        rows.next_with(|tuple| Row::from_sql_tuple_with_dialect(tuple, dialect))
+    }
+}
+
+#[test]
+fn test_iterate_sql_insertions_with_dialect() {
+    use field_types::PageTitle;
+    use schemas::Category;
+
+    let sql = br"INSERT INTO `category` VALUES (1,'Bell\a',0,0,0),(2,'Tab\t',0,0,0);";
+    let titles = |dialect| {
+        let mut rows = iterate_sql_insertions_with_dialect::<Category>(sql, dialect);
+        let titles: Vec<_> = (&mut rows).map(|category| category.title).collect();
+        (titles, rows.finish().is_ok())
+    };
+    assert_eq!(
+        titles(SqlDialect::default().with_escape(b'a', 0x07)),
+        (
+            vec![
+                PageTitle("Bell\u{7}".to_string()),
+                PageTitle("Tab\t".to_string())
+            ],
+            true
+        )
+    );
+    assert_eq!(titles(SqlDialect::default()), (vec![], false));
+    assert_eq!(
+        iterate_sql_insertions::<Category>(sql).into_iter().count(),
+        0
+    );
+}
+
+/// Statistics collected by [`iterate_sql_insertions_with_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseStats {
+    /// The number of rows parsed so far.
+    pub rows: usize,
+    /// The number of bytes of the SQL consumed so far, including everything before the first row.
+    pub bytes: usize,
+    /// The length in bytes of the longest SQL tuple parsed so far, including the parentheses.
+    pub max_tuple_len: usize,
+}
+
+/**
+Like [`iterate_sql_insertions`], but also returns [`ParseStats`] that are updated after every row.
+
+The statistics are shared with the iterator, so they can be checked during or after iteration.
+A large [`max_tuple_len`](ParseStats::max_tuple_len) can reveal anomalies like a gigantic row.
 */
-#[must_use = "the return type implements `Iterator` as a mutable reference, and does nothing unless consumed"]
-pub fn iterate_sql_insertions<'input, Row>(
+pub fn iterate_sql_insertions_with_stats<'input, Row>(
     sql: &'input [u8],
-) -> ParserIterator<&'input [u8], Error<'input>, impl FnMut(&'input [u8]) -> IResult<'input, Row>>
+) -> (SqlInsertionsWithStats<'input, Row>, Rc<RefCell<ParseStats>>)
 where
     Row: FromSqlTuple<'input> + 'input,
 {
-    let sql = &sql[sql.find("INSERT INTO").expect("INSERT INTO statement")..];
-    iterator(
-        sql,
-        preceded(
-            alt((
+    let stats = Rc::new(RefCell::new(ParseStats::default()));
+    let rows = SqlInsertionsWithStats {
+        rows: iterate_sql_insertions(sql),
+        sql_len: sql.len(),
+        stats: Rc::clone(&stats),
+    };
+    (rows, stats)
+}
+
+/**
+The iterator returned by [`iterate_sql_insertions_with_stats`].
+
+Like [`SqlInsertions`], it implements [`Iterator`] as a mutable reference,
+so that [`finish`](Self::finish) can be called after iterating.
+*/
+#[derive(Debug)]
+#[must_use = "implements `Iterator` as a mutable reference, and does nothing unless consumed"]
+pub struct SqlInsertionsWithStats<'input, Row> {
+    rows: SqlInsertions<'input, Row>,
+    sql_len: usize,
+    stats: Rc<RefCell<ParseStats>>,
+}
+
+impl<'input, Row> SqlInsertionsWithStats<'input, Row> {
+    /// The input that hasn't been parsed yet.
+    pub fn remaining(&self) -> &'input [u8] {
+        self.rows.remaining()
+    }
+
+    /// Returns the rest of the input or the error that stopped iteration,
+    /// like [`SqlInsertions::finish`].
+    pub fn finish(self) -> IResult<'input, ()> {
+        self.rows.finish()
+    }
+}
+
+impl<'input, Row> Iterator for &mut SqlInsertionsWithStats<'input, Row>
+where
+    Row: FromSqlTuple<'input> + 'input,
+{
+    type Item = Row;
+
+    fn next(&mut self) -> Option<Row> {
+        let SqlInsertionsWithStats {
+            rows,
+            sql_len,
+            stats,
+        } = &mut **self;
+        rows.next_with(|tuple| {
+            let (rest, row) = Row::from_sql_tuple(tuple)?;
+            let mut stats = stats.borrow_mut();
+            stats.rows += 1;
+            stats.bytes = *sql_len - rest.len();
+            stats.max_tuple_len = stats.max_tuple_len.max(tuple.len() - rest.len());
+            Ok((rest, row))
+        })
+    }
+}
+
+/**
+Folds the rows in a SQL dump into an accumulator, like [`Iterator::fold`],
+and reports whether parsing reached the end of the `INSERT` statements.
+
+Returns the accumulator and the unparsed input after the last `INSERT` statement if successful.
+If a row fails to parse or the input ends in the middle of an `INSERT` statement,
+returns the accumulator containing the rows before the error, along with the error.
+*/
+#[allow(clippy::type_complexity)]
+pub fn fold_sql_insertions<'input, Row, Acc, F>(
+    sql: &'input [u8],
+    init: Acc,
+    f: F,
+) -> Result<(Acc, &'input [u8]), (Acc, nom::Err<Error<'input>>)>
+where
+    Row: FromSqlTuple<'input> + 'input,
+    F: FnMut(Acc, Row) -> Acc,
+{
+    let mut rows = iterate_sql_insertions(sql);
+    let acc = (&mut rows).fold(init, f);
+    match rows.finish() {
+        Ok((rest, ())) => Ok((acc, rest)),
+        Err(e) => Err((acc, e)),
+    }
+}
+
+/**
+Like [`iterate_sql_insertions`], but when a row fails to parse,
+yields the error and skips to the next row instead of stopping.
+
+The end of the broken row is found by skipping over string literals to the closing `)`,
+or if that fails, by searching for the next `),(`.
+This trades correctness for robustness, so only use it when occasional
+corrupt rows are acceptable, and check how many errors were yielded.
+*/
+pub fn iterate_sql_insertions_lossy<'input, Row>(
+    sql: &'input [u8],
+) -> impl Iterator<Item = Result<Row, Error<'input>>>
+where
+    Row: FromSqlTuple<'input> + 'input,
+{
+    let mut input = Some(skip_to_insertions(sql));
+    std::iter::from_fn(move || {
+        let rest = match insertion_start(input?) {
+            Ok((rest, _)) => rest,
+            Err(_) => {
+                input = None;
+                return None;
+            }
+        };
+        match Row::from_sql_tuple(rest) {
+            Ok((rest, row)) => {
+                input = Some(rest);
+                Some(Ok(row))
+            }
+            Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
+                input = match raw_tuple(rest) {
+                    Ok((rest, _)) => Some(rest),
+                    Err(_) => rest.find("),(").map(|pos| &rest[pos + 1..]),
+                };
+                Some(Err(e))
+            }
+            Err(nom::Err::Incomplete(_)) => {
+                input = None;
+                None
+            }
+        }
+    })
+}
+
+/**
+Checks whether the rest of the input returned by [`SqlInsertions::finish`] or the `finish` method
+of the other iterators over rows is the end of the last `INSERT` statement, meaning that all rows were parsed.
+
+The rest of the input must start with the `;` that ends an `INSERT` statement,
+optionally preceded by whitespace such as a line break,
+and must not contain any more `INSERT` statements.
+*/
+pub fn is_end_of_insertions(remaining: &[u8]) -> bool {
+    remaining
+        .trim_start_with(|c| c.is_ascii_whitespace())
+        .starts_with(b";")
+        && remaining.find("INSERT INTO").is_none()
+}
+
+fn skip_to_insertions(sql: &[u8]) -> &[u8] {
+    &sql[sql.find("INSERT INTO").expect("INSERT INTO statement")..]
+}
+
+/**
+Splits a SQL dump into at most `target_chunks` pieces of roughly equal length,
+each of which can be parsed independently with [`iterate_sql_insertions`],
+for instance on separate threads.
+
+Every chunk after the first starts at the beginning of an `INSERT` statement.
+A line break followed by `INSERT INTO` can't occur inside a string literal,
+because line breaks in strings are escaped in the dump,
+so unlike splitting on `),(`, this never splits a row.
+Since a chunk can only end between statements, a dump with a few long statements
+may be split into fewer than `target_chunks` pieces.
+The chunks together contain the whole input, in order.
+
+# Panics
+Panics if `target_chunks` is 0.
+*/
+pub fn split_into_statement_chunks(sql: &[u8], target_chunks: usize) -> Vec<&[u8]> {
+    assert!(target_chunks > 0, "must split into at least one chunk");
+    let chunk_len = sql.len() / target_chunks;
+    // The first chunk must contain the first statement, not just the header.
+    let first_statement = sql.find("INSERT INTO").unwrap_or(sql.len());
+    let mut chunks = Vec::with_capacity(target_chunks);
+    let mut start = 0;
+    while chunks.len() + 1 < target_chunks {
+        let search_from = (start + chunk_len).max(first_statement).min(sql.len());
+        let boundary = match sql[search_from..].find("\nINSERT INTO `") {
+            Some(pos) => search_from + pos + 1,
+            None => break,
+        };
+        chunks.push(&sql[start..boundary]);
+        start = boundary;
+    }
+    chunks.push(&sql[start..]);
+    chunks
+}
+
+#[test]
+fn test_split_into_statement_chunks() {
+    use crate::schemas::LinkTarget;
+    use std::fmt::Write as _;
+
+    let mut sql = String::from(
+        "-- MySQL dump\nCREATE TABLE `linktarget` (\n  `lt_id` bigint(20) unsigned NOT NULL\n);\n",
+    );
+    for statement in 0..100 {
+        sql.push_str("INSERT INTO `linktarget` VALUES ");
+        for i in 0..10 {
+            let id = statement * 10 + i + 1;
+            if i > 0 {
+                sql.push(',');
+            }
+            // Titles containing the text that chunks are split at must not be split.
+            write!(
+                sql,
+                r"({},0,'A\nINSERT INTO `linktarget` VALUES (0,0,\'{}\')')",
+                id, id
+            )
+            .unwrap();
+        }
+        sql.push_str(";\n");
+    }
+    sql.push_str("/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;\n");
+    let sql = sql.as_bytes();
+
+    let all: Vec<_> = iterate_sql_insertions::<LinkTarget>(sql)
+        .into_iter()
+        .collect();
+    assert_eq!(all.len(), 1000);
+    for target_chunks in [1, 2, 7, 100, 1000] {
+        let chunks = split_into_statement_chunks(sql, target_chunks);
+        assert!(chunks.len() <= target_chunks && chunks.len() <= 100);
+        assert_eq!(chunks.concat(), sql);
+        let mut from_chunks = Vec::new();
+        for chunk in &chunks {
+            let mut rows = iterate_sql_insertions::<LinkTarget>(chunk);
+            from_chunks.extend(&mut rows);
+            assert_eq!(
+                rows.finish().map(|(rest, _)| is_end_of_insertions(rest)),
+                Ok(true)
+            );
+        }
+        assert_eq!(from_chunks, all);
+    }
+    assert_eq!(split_into_statement_chunks(sql, 7).len(), 7);
+}
+
+/**
+Splits the `INSERT` statements in a SQL dump into the SQL tuples representing rows,
+without parsing the fields in them. Each item starts with `(` and ends with `)`.
+Commas and parentheses inside string literals are skipped over.
+
+This is useful to count rows quickly or to parse only some of them
+with [`FromSqlTuple::from_sql_tuple`].
+*/
+pub fn raw_tuples(sql: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut tuples = iterator(
+        skip_to_insertions(sql),
+        preceded(insertion_start, raw_tuple),
+    );
+    std::iter::from_fn(move || (&mut tuples).next())
+}
+
+/**
+Parses the comma between two rows or the start of an `INSERT` statement.
+The comma is by far the most common case, so it is tried first,
+and the statement prelude is only parsed when there is no comma.
+*/
+fn insertion_start(s: &[u8]) -> IResult<'_, &[u8]> {
+    alt((tag(","), statement_start))(s)
+}
+
+/**
+Parses the end of the previous `INSERT` statement, if any, and the start of the next one.
+
+If the input ends after the `;` that ends a statement, returns an error rather than
+[`nom::Err::Incomplete`], because that is the end of the insertions in a chunk
+returned by [`split_into_statement_chunks`].
+*/
+fn statement_start(s: &[u8]) -> IResult<'_, &[u8]> {
+    let (rest, _) = pair(multispace0, opt(tag(";")))(s)?;
+    let (rest, _) = nom::character::complete::multispace0(rest)?;
+    if rest.is_empty() {
+        return Err(nom::Err::Error(Error::from_error_kind(
+            rest,
+            ErrorKind::Eof,
+        )));
+    }
+    let (rest, _) = tuple((
+        tag(B("INSERT INTO `")),
+        take_while(|b: u8| b == b'_' || b.is_ascii_lowercase() || b.is_ascii_digit()),
+        tag(B("` VALUES ")),
+    ))(rest)?;
+    Ok((rest, &s[..s.len() - rest.len()]))
+}
+
+/// Parses the start of an `INSERT` statement or a comma, followed by a row.
+fn sql_insertion<'input, Row>() -> impl FnMut(&'input [u8]) -> IResult<'input, Row>
+where
+    Row: FromSqlTuple<'input> + 'input,
+{
+    preceded(insertion_start, FromSqlTuple::from_sql_tuple)
+}
+
+/// Recognizes a SQL tuple without parsing the fields.
+fn raw_tuple(s: &[u8]) -> IResult<'_, &[u8]> {
+    context(
+        "SQL tuple",
+        recognize(tuple((
+            char('('),
+            many0_count(alt((
                 recognize(tuple((
-                    opt(multispace0),
-                    opt(tag(";")),
-                    opt(multispace0),
-                    tuple((
-                        tag(B("INSERT INTO `")),
-                        take_while(|b: u8| b == b'_' || b.is_ascii_lowercase()),
-                        tag(B("` VALUES ")),
-                    )),
+                    char('\''),
+                    opt(escaped(is_not(B("\\'")), '\\', anychar)),
+                    char('\''),
                 ))),
-                tag(","),
-            )),
-            FromSqlTuple::from_sql_tuple,
-        ),
-    )
+                is_not(B("'()")),
+            ))),
+            char(')'),
+        ))),
+    )(s)
+}
+
+#[test]
+fn test_iterate_sql_insertions_with_stats() {
+    use crate::schemas::LinkTarget;
+
+    let sql = br"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,'Much_longer_title');
+INSERT INTO `linktarget` VALUES (3,0,'c');
+/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;
+";
+    let (mut rows, stats) = iterate_sql_insertions_with_stats::<LinkTarget>(sql);
+    let count = (&mut rows).count();
+    assert_eq!(count, 3);
+    let (rest, ()) = rows.finish().unwrap();
+    assert_eq!(
+        *stats.borrow(),
+        ParseStats {
+            rows: count,
+            bytes: sql.len() - rest.len(),
+            max_tuple_len: "(2,0,'Much_longer_title')".len(),
+        }
+    );
+}
+
+#[test]
+fn test_many_small_statements() {
+    use crate::schemas::LinkTarget;
+    use std::fmt::Write as _;
+
+    // Like the output of `mysqldump --net-buffer-length` with a tiny buffer:
+    // many statements for the same table, with varying numbers of rows.
+    let mut sql = String::from("-- header\n");
+    let mut id = 0;
+    for statement in 0..500 {
+        sql.push_str("INSERT INTO `linktarget` VALUES ");
+        for i in 0..statement % 4 + 1 {
+            id += 1;
+            if i > 0 {
+                sql.push(',');
+            }
+            write!(sql, "({},0,'Title_{}')", id, id).unwrap();
+        }
+        sql.push_str(";\n");
+    }
+    sql.push_str("/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;\n");
+
+    let mut rows = iterate_sql_insertions::<LinkTarget>(sql.as_bytes());
+    let ids: Vec<_> = (&mut rows).map(|row| row.id.into_inner()).collect();
+    assert_eq!(ids, (1..=id).collect::<Vec<_>>());
+    assert_eq!(
+        rows.finish().map(|(rest, _)| is_end_of_insertions(rest)),
+        Ok(true)
+    );
+    assert_eq!(raw_tuples(sql.as_bytes()).count(), id as usize);
+}
+
+#[test]
+fn test_raw_tuples() {
+    let sql = br"-- header
+INSERT INTO `redirect` VALUES (1,0,'A_(disambiguation)','',NULL),(2,0,'B','','it\'s, (really) \\'),(3,0,'C','','');
+INSERT INTO `redirect` VALUES (4,0,'D','','');
+";
+    let tuples: Vec<_> = raw_tuples(sql).collect();
+    assert_eq!(
+        tuples,
+        [
+            B(r"(1,0,'A_(disambiguation)','',NULL)"),
+            B(r"(2,0,'B','','it\'s, (really) \\')"),
+            B(r"(3,0,'C','','')"),
+            B(r"(4,0,'D','','')"),
+        ]
+    );
+    assert!(tuples
+        .iter()
+        .all(|tuple| tuple.starts_with(b"(") && tuple.ends_with(b")")));
+}
+
+#[test]
+fn test_iterate_sql_insertions_with_progress() {
+    use crate::schemas::LinkTarget;
+
+    let sql =
+        b"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,'b'),(3,0,'c'),(4,0,'d'),(5,0,'e');\n";
+    let mut progress = Vec::new();
+    let rows = iterate_sql_insertions_with_progress::<LinkTarget, _>(sql, 2, |rows, bytes| {
+        progress.push((rows, bytes))
+    })
+    .count();
+    assert_eq!(rows, 5);
+    let row_end = |n| sql.find(format!("({},0,", n)).unwrap() + "(1,0,'a')".len();
+    assert_eq!(progress, [(2, row_end(2)), (4, row_end(4))]);
+}
+
+/// The `INSERT` statements for one table in a SQL dump, yielded by [`iterate_tables`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TableChunk<'input> {
+    /// The name of the table, such as `page`.
+    pub name: &'input str,
+    /// The SQL starting at the first `INSERT` statement for the table,
+    /// which can be passed to [`iterate_sql_insertions`].
+    pub sql: &'input [u8],
+}
+
+/**
+Splits a SQL script that creates several tables into the sections containing
+the `INSERT` statements for each table, so that each section can be parsed
+with [`iterate_sql_insertions`] using the appropriate schema.
+
+Tables without any `INSERT` statements are skipped.
+*/
+pub fn iterate_tables(sql: &[u8]) -> impl Iterator<Item = TableChunk<'_>> {
+    const CREATE_TABLE: &str = "CREATE TABLE `";
+    let mut rest = sql;
+    std::iter::from_fn(move || loop {
+        let name_start = rest.find(CREATE_TABLE)? + CREATE_TABLE.len();
+        let after_name_start = &rest[name_start..];
+        let name_end = after_name_start.find_byte(b'`')?;
+        let name = std::str::from_utf8(&after_name_start[..name_end]).ok()?;
+        let table = &after_name_start[name_end..];
+        let table_end = table.find(CREATE_TABLE).unwrap_or(table.len());
+        rest = &table[table_end..];
+        if let Some(insert_start) = table[..table_end].find("INSERT INTO") {
+            return Some(TableChunk {
+                name,
+                sql: &table[insert_start..table_end],
+            });
+        }
+    })
+}
+
+/**
+Extracts the names and types of the columns from the `CREATE TABLE` statement
+at the beginning of a SQL dump, in the order in which they appear in the `INSERT` statements.
+
+Comparing the columns with those expected by a [schema](schemas) before iterating
+reveals changes in the layout of a table that would cause parsing to fail.
+The type of a column is its SQL data type without any constraints,
+such as `int(8) unsigned` or `varbinary(255)`.
+
+# Errors
+Fails if there is no complete `CREATE TABLE` statement.
+*/
+pub fn parse_create_table(sql: &[u8]) -> Result<Vec<(String, String)>, Error<'_>> {
+    fn error<'a>(input: &'a [u8], label: &'static str) -> Error<'a> {
+        Error::ErrorWithContexts(vec![error::ParseTypeContext::Single {
+            input: input.into(),
+            label,
+        }])
+    }
+
+    let start = sql
+        .find("CREATE TABLE `")
+        .ok_or_else(|| error(sql, "CREATE TABLE statement"))?;
+    let mut columns = Vec::new();
+    for line in sql[start..].lines().skip(1) {
+        let line = line.trim();
+        if line.starts_with(b")") {
+            return Ok(columns);
+        }
+        if let Some(definition) = line.strip_prefix(b"`") {
+            let name_end = definition
+                .find_byte(b'`')
+                .ok_or_else(|| error(line, "column name"))?;
+            let name = definition[..name_end].to_str_lossy().into_owned();
+            let column_type = definition[name_end + 1..]
+                .fields()
+                .take_while(|token| !token[0].is_ascii_uppercase())
+                .map(|token| token.to_str_lossy())
+                .collect::<Vec<_>>()
+                .join(" ");
+            columns.push((name, column_type.trim_end_matches(',').to_string()));
+        }
+    }
+    Err(error(&sql[start..], "end of CREATE TABLE statement"))
+}
+
+#[test]
+fn test_iterate_tables() {
+    use crate::{
+        field_types::{CategoryId, PageCount, PageId, PageNamespace, PageTitle},
+        schemas::{Category, Redirect},
+    };
+
+    let sql = br"
+-- Table structure for table `category`
+DROP TABLE IF EXISTS `category`;
+CREATE TABLE `category` (
+  `cat_id` int(10) unsigned NOT NULL AUTO_INCREMENT
+) ENGINE=InnoDB;
+INSERT INTO `category` VALUES (1,'Nouns',10,2,0),(2,'Verbs',5,0,0);
+INSERT INTO `category` VALUES (3,'Adjectives',3,0,1);
+UNLOCK TABLES;
+
+-- Table structure for table `redirect`
+DROP TABLE IF EXISTS `redirect`;
+CREATE TABLE `redirect` (
+  `rd_from` int(8) unsigned NOT NULL DEFAULT 0
+) ENGINE=InnoDB;
+INSERT INTO `redirect` VALUES (605368,1,'Foo','','');
+UNLOCK TABLES;
+";
+    let tables: Vec<_> = iterate_tables(sql).collect();
+    assert_eq!(
+        tables.iter().map(|table| table.name).collect::<Vec<_>>(),
+        ["category", "redirect"]
+    );
+
+    let categories: Vec<Category> = iterate_sql_insertions(tables[0].sql).collect();
+    assert_eq!(categories.len(), 3);
+    assert_eq!(
+        categories[2],
+        Category {
+            id: CategoryId(3),
+            title: PageTitle("Adjectives".to_string()),
+            pages: PageCount(3),
+            subcats: PageCount(0),
+            files: PageCount(1),
+        }
+    );
+
+    let redirects: Vec<Redirect> = iterate_sql_insertions(tables[1].sql).collect();
+    assert_eq!(
+        redirects,
+        [Redirect {
+            from: PageId(605368),
+            namespace: PageNamespace(1),
+            title: PageTitle("Foo".to_string()),
+            interwiki: Some(""),
+            fragment: Some(String::new()),
+        }]
+    );
+}
+
+#[test]
+fn test_parse_create_table() {
+    let sql = br"
+DROP TABLE IF EXISTS `page`;
+/*!40101 SET @saved_cs_client     = @@character_set_client */;
+/*!40101 SET character_set_client = utf8 */;
+CREATE TABLE `page` (
+  `page_id` int(8) unsigned NOT NULL AUTO_INCREMENT,
+  `page_namespace` int(11) NOT NULL DEFAULT 0,
+  `page_title` varbinary(255) NOT NULL DEFAULT '',
+  `page_is_redirect` tinyint(1) unsigned NOT NULL DEFAULT 0,
+  `page_is_new` tinyint(1) unsigned NOT NULL DEFAULT 0,
+  `page_random` double unsigned NOT NULL DEFAULT 0,
+  `page_touched` binary(14) NOT NULL,
+  `page_links_updated` varbinary(14) DEFAULT NULL,
+  `page_latest` int(8) unsigned NOT NULL DEFAULT 0,
+  `page_len` int(8) unsigned NOT NULL DEFAULT 0,
+  `page_content_model` varbinary(32) DEFAULT NULL,
+  `page_lang` varbinary(35) DEFAULT NULL,
+  PRIMARY KEY (`page_id`),
+  UNIQUE KEY `page_name_title` (`page_namespace`,`page_title`),
+  KEY `page_random` (`page_random`),
+  KEY `page_len` (`page_len`),
+  KEY `page_redirect_namespace_len` (`page_is_redirect`,`page_namespace`,`page_len`)
+) ENGINE=InnoDB AUTO_INCREMENT=7812345 DEFAULT CHARSET=binary ROW_FORMAT=COMPRESSED;
+/*!40101 SET character_set_client = @saved_cs_client */;
+INSERT INTO `page` VALUES (1,0,'Main_Page',0,0,0.5,'20200101000000',NULL,1,1,'wikitext',NULL);
+";
+    let columns = parse_create_table(sql).unwrap();
+    assert_eq!(
+        columns
+            .iter()
+            .map(|(name, column_type)| (name.as_str(), column_type.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            ("page_id", "int(8) unsigned"),
+            ("page_namespace", "int(11)"),
+            ("page_title", "varbinary(255)"),
+            ("page_is_redirect", "tinyint(1) unsigned"),
+            ("page_is_new", "tinyint(1) unsigned"),
+            ("page_random", "double unsigned"),
+            ("page_touched", "binary(14)"),
+            ("page_links_updated", "varbinary(14)"),
+            ("page_latest", "int(8) unsigned"),
+            ("page_len", "int(8) unsigned"),
+            ("page_content_model", "varbinary(32)"),
+            ("page_lang", "varbinary(35)"),
+        ]
+    );
+    assert!(parse_create_table(b"INSERT INTO `page` VALUES ").is_err());
+}
+
+#[test]
+fn test_fold_sql_insertions() {
+    use crate::schemas::LinkTarget;
+
+    let count = |count, _: LinkTarget| count + 1;
+    let sql = b"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,'b');\n/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;\n";
+    assert_eq!(
+        fold_sql_insertions(sql, 0, count),
+        Ok((
+            2,
+            B(";\n/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;\n")
+        ))
+    );
+
+    let sql = b"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,'b'),(3,'c',0);\n/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;\n";
+    match fold_sql_insertions(sql, 0, count) {
+        Err((2, nom::Err::Failure(e))) => {
+            assert!(e.to_string().contains("the field “namespace”"), "{}", e)
+        }
+        other => panic!("expected failure after 2 rows, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_is_end_of_insertions() {
+    use crate::schemas::LinkTarget;
+
+    let sql = b"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,'b');\nINSERT INTO `linktarget` VALUES (3,0,'c');\n/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;\n";
+    let mut rows = iterate_sql_insertions::<LinkTarget>(sql);
+    assert_eq!((&mut rows).count(), 3);
+    assert_eq!(
+        rows.finish().map(|(rest, _)| is_end_of_insertions(rest)),
+        Ok(true)
+    );
+
+    let sql = b"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,'b',0);\n";
+    let mut rows = iterate_sql_insertions::<LinkTarget>(sql);
+    assert_eq!((&mut rows).count(), 1);
+    assert!(matches!(rows.finish(), Err(nom::Err::Failure(_))));
+
+    let sql = b"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,";
+    let mut rows = iterate_sql_insertions::<LinkTarget>(sql);
+    assert_eq!((&mut rows).count(), 1);
+    assert!(matches!(rows.finish(), Err(nom::Err::Incomplete(_))));
+
+    assert!(!is_end_of_insertions(b",(2,0,'b');\n"));
+    assert!(!is_end_of_insertions(
+        b";\nINSERT INTO `linktarget` VALUES (3,0,'c');\n"
+    ));
+}
+
+#[test]
+fn test_sql_insertions_in_struct() {
+    use crate::schemas::LinkTarget;
+
+    struct Titles<'input> {
+        rows: SqlInsertions<'input, LinkTarget>,
+    }
+
+    impl<'input> Iterator for Titles<'input> {
+        type Item = String;
+
+        fn next(&mut self) -> Option<String> {
+            (&mut self.rows).next().map(|row| row.title.into_inner())
+        }
+    }
+
+    let sql = b"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,'b');\n/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;\n";
+    let mut titles = Titles {
+        rows: iterate_sql_insertions(sql),
+    };
+    assert_eq!((&mut titles).collect::<Vec<_>>(), ["a", "b"]);
+    assert!(titles.rows.remaining().starts_with(b";\n/*"));
+    assert_eq!(
+        titles
+            .rows
+            .finish()
+            .map(|(rest, _)| is_end_of_insertions(rest)),
+        Ok(true)
+    );
+}
+
+#[test]
+fn test_sql_insertions_into_iter() {
+    use crate::schemas::LinkTarget;
+
+    let sql = b"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,'b');\n/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;\n";
+    let mut ids = Vec::new();
+    for LinkTarget { id, .. } in iterate_sql_insertions(sql) {
+        ids.push(id.into_inner());
+    }
+    assert_eq!(ids, [1, 2]);
+
+    let mut rows = iterate_sql_insertions::<LinkTarget>(sql).into_iter();
+    assert_eq!(rows.by_ref().count(), 2);
+    assert_eq!(
+        rows.into_inner()
+            .finish()
+            .map(|(rest, _)| is_end_of_insertions(rest)),
+        Ok(true)
+    );
+}
+
+#[test]
+fn test_iterate_sql_insertions_lossy() {
+    use crate::schemas::LinkTarget;
+
+    let sql = br"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,'b',0),(3,0,'c');
+INSERT INTO `linktarget` VALUES (4,0,'d'),(5,0,'unterminated),(6,0,'f'),(7,0,'g');
+/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;
+";
+    let rows: Vec<_> = iterate_sql_insertions_lossy::<LinkTarget>(sql)
+        .map(|row| row.map(|row| row.id.into_inner()).map_err(|_| ()))
+        .collect();
+    assert_eq!(rows, [Ok(1), Err(()), Ok(3), Ok(4), Err(()), Ok(6), Ok(7)]);
+}
+
+#[test]
+fn test_crlf_line_endings() {
+    use crate::schemas::LinkTarget;
+
+    let sql = b"-- MySQL dump 10.19\r\n--\r\n-- Host: db1106    Database: enwiki\r\n-- ------------------------------------------------------\r\n\r\nDROP TABLE IF EXISTS `linktarget`;\r\nCREATE TABLE `linktarget` (\r\n  `lt_id` bigint(20) unsigned NOT NULL AUTO_INCREMENT,\r\n  `lt_namespace` int(11) NOT NULL,\r\n  `lt_title` varbinary(255) NOT NULL,\r\n  PRIMARY KEY (`lt_id`)\r\n) ENGINE=InnoDB DEFAULT CHARSET=binary;\r\n\r\nINSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,'b');\r\nINSERT INTO `linktarget` VALUES (3,0,'c') ;\r\n/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;\r\n";
+    let ids = |rows: &mut SqlInsertions<LinkTarget>| -> Vec<_> {
+        rows.map(|row| row.id.into_inner()).collect()
+    };
+
+    let mut rows = iterate_sql_insertions::<LinkTarget>(sql);
+    assert_eq!(ids(&mut rows), [1, 2, 3]);
+    assert_eq!(
+        rows.finish().map(|(rest, _)| is_end_of_insertions(rest)),
+        Ok(true)
+    );
+    assert_eq!(raw_tuples(sql).count(), 3);
+    assert!(is_end_of_insertions(b"\r\n;\r\n"));
+
+    let columns: Vec<_> = parse_create_table(sql)
+        .unwrap()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(columns, ["lt_id", "lt_namespace", "lt_title"]);
+
+    let tables: Vec<_> = iterate_tables(sql).map(|table| table.name).collect();
+    assert_eq!(tables, ["linktarget"]);
+
+    let chunks = split_into_statement_chunks(sql, 2);
+    assert_eq!(chunks.concat(), sql);
+    let from_chunks: Vec<_> = chunks
+        .iter()
+        .flat_map(|chunk| ids(&mut iterate_sql_insertions(chunk)))
+        .collect();
+    assert_eq!(from_chunks, [1, 2, 3]);
+
+    let second_statement = sql.find("INSERT INTO `linktarget` VALUES (3").unwrap();
+    let mut rows = iterate_sql_insertions_from_offset::<LinkTarget>(sql, second_statement - 1);
+    assert_eq!(ids(&mut rows), [3]);
+
+    let mut reader = StatementReader::new(&sql[..]);
+    let mut statements = Vec::new();
+    while let Some(statement) = reader.next_statement().unwrap() {
+        statements.push(statement.to_vec());
+    }
+    assert!(statements.iter().all(|statement| statement.ends_with(b";")));
+    assert_eq!(statements.len(), 2);
+
+    #[cfg(feature = "utils")]
+    assert_eq!(utils::detect_wiki(sql), Some("enwiki"));
+}
+
+/**
+Reads a SQL dump one `INSERT` statement at a time from a [`BufRead`](std::io::BufRead),
+for files that are too large to load into memory or can't be memory-mapped,
+for instance when they are decompressed on the fly.
+
+`mysqldump` writes each `INSERT` statement on its own line,
+so only one line is buffered at a time. Lines that are not `INSERT` statements are skipped.
+
+Because the buffer is reused for each statement, [`rows`](Self::rows) requires a `Row`
+that owns its data, such as [`LinkTarget`](schemas::LinkTarget), and doesn't work
+with schemas that borrow from the input, such as [`Page`](schemas::Page).
+To parse those, call [`next_statement`](Self::next_statement) and pass each statement
+to [`iterate_sql_insertions`], processing the rows before reading the next statement
+or converting them with `to_owned_row`, like [`Page::to_owned_row`](schemas::Page::to_owned_row).
+*/
+#[derive(Debug)]
+pub struct StatementReader<R> {
+    reader: R,
+    statement: Vec<u8>,
+}
+
+impl<R: std::io::BufRead> StatementReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            statement: Vec::new(),
+        }
+    }
+
+    /// Reads the next `INSERT` statement, including the terminating `;`,
+    /// or returns `None` at the end of the input.
+    pub fn next_statement(&mut self) -> std::io::Result<Option<&[u8]>> {
+        loop {
+            self.statement.clear();
+            if self.reader.read_until(b'\n', &mut self.statement)? == 0 {
+                return Ok(None);
+            }
+            if self.statement.starts_with(b"INSERT INTO ") {
+                let len = self
+                    .statement
+                    .trim_end_with(|c| c == '\n' || c == '\r')
+                    .len();
+                self.statement.truncate(len);
+                return Ok(Some(&self.statement));
+            }
+        }
+    }
+
+    /// Returns an iterator over the rows in all the remaining `INSERT` statements.
+    pub fn rows<Row>(self) -> StatementRows<R, Row>
+    where
+        Row: for<'input> FromSqlTuple<'input>,
+    {
+        StatementRows {
+            reader: self,
+            rows: Vec::new().into_iter(),
+            error: None,
+            done: false,
+        }
+    }
+}
+
+/**
+The iterator returned by [`StatementReader::rows`].
+
+Yields an [`std::io::Error`] if reading fails or if a statement fails to parse,
+with the parse error converted to a message with kind [`InvalidData`](std::io::ErrorKind::InvalidData),
+and then stops. The rows of the statement that were parsed before the error are yielded first.
+*/
+#[derive(Debug)]
+pub struct StatementRows<R, Row> {
+    reader: StatementReader<R>,
+    rows: std::vec::IntoIter<Row>,
+    error: Option<std::io::Error>,
+    done: bool,
+}
+
+impl<R, Row> StatementRows<R, Row>
+where
+    R: std::io::BufRead,
+    Row: for<'input> FromSqlTuple<'input>,
+{
+    fn parse_next_statement(&mut self) -> std::io::Result<Option<Vec<Row>>> {
+        let statement = match self.reader.next_statement()? {
+            Some(statement) => statement,
+            None => return Ok(None),
+        };
+        let mut parse_row = sql_insertion::<Row>();
+        let mut input = statement;
+        let mut rows = Vec::new();
+        while !is_end_of_insertions(input) {
+            match parse_row(input) {
+                Ok((rest, row)) => {
+                    rows.push(row);
+                    input = rest;
+                }
+                Err(e) => {
+                    let message = match e {
+                        nom::Err::Error(e) | nom::Err::Failure(e) => e.to_string(),
+                        nom::Err::Incomplete(_) => "incomplete INSERT statement".to_string(),
+                    };
+                    self.error = Some(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        message,
+                    ));
+                    break;
+                }
+            }
+        }
+        Ok(Some(rows))
+    }
+}
+
+impl<R, Row> Iterator for StatementRows<R, Row>
+where
+    R: std::io::BufRead,
+    Row: for<'input> FromSqlTuple<'input>,
+{
+    type Item = std::io::Result<Row>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(row) = self.rows.next() {
+                return Some(Ok(row));
+            }
+            if let Some(e) = self.error.take() {
+                self.done = true;
+                return Some(Err(e));
+            }
+            if self.done {
+                return None;
+            }
+            match self.parse_next_statement() {
+                Ok(Some(rows)) => self.rows = rows.into_iter(),
+                Ok(None) => self.done = true,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_statement_reader() {
+    use crate::schemas::LinkTarget;
+    use std::io::Cursor;
+
+    let sql = b"-- MySQL dump\r\n/*!40000 ALTER TABLE `linktarget` DISABLE KEYS */;\r\nINSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,'b;c');\r\nINSERT INTO `linktarget` VALUES (3,10,'d');\r\n/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;\r\n";
+    let titles = StatementReader::new(Cursor::new(&sql[..]))
+        .rows::<LinkTarget>()
+        .map(|row| row.map(|row| (row.id.into_inner(), row.title.into_inner())))
+        .collect::<std::io::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(
+        titles,
+        [
+            (1, "a".to_string()),
+            (2, "b;c".to_string()),
+            (3, "d".to_string())
+        ]
+    );
+
+    let mut reader = StatementReader::new(Cursor::new(&sql[..]));
+    let mut pages = Vec::new();
+    while let Some(statement) = reader.next_statement().unwrap() {
+        pages
+            .extend(iterate_sql_insertions::<LinkTarget>(statement).map(|row| row.id.into_inner()));
+    }
+    assert_eq!(pages, [1, 2, 3]);
+
+    let sql = b"INSERT INTO `linktarget` VALUES (1,0,'a');\nINSERT INTO `linktarget` VALUES (2,'b',0);\nINSERT INTO `linktarget` VALUES (3,0,'c');\n";
+    let mut rows = StatementReader::new(Cursor::new(&sql[..])).rows::<LinkTarget>();
+    assert!(matches!(rows.next(), Some(Ok(_))));
+    let error = rows.next().unwrap().unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert!(rows.next().is_none());
+
+    let ids = |sql: &[u8]| {
+        StatementReader::new(Cursor::new(sql))
+            .rows::<LinkTarget>()
+            .map(|row| row.map(|row| row.id.into_inner()).map_err(|e| e.kind()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        ids(b"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,'b') ;\r\nINSERT INTO `linktarget` VALUES (3,0,'c');\r\n"),
+        [Ok(1), Ok(2), Ok(3)]
+    );
+    assert_eq!(
+        ids(b"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,'b',0);\nINSERT INTO `linktarget` VALUES (3,0,'c');\n"),
+        [Ok(1), Err(std::io::ErrorKind::InvalidData)]
+    );
 }
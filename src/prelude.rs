@@ -0,0 +1,37 @@
+/*!
+Re-exports the items that most programs using this crate need,
+so that they can be imported with one `use` declaration:
+
+```
+use parse_mediawiki_sql::prelude::*;
+
+let sql = br"INSERT INTO `page` VALUES (10,0,'Main_Page',0,0,0.5,'20230102030405',NULL,1234,5678,'wikitext',NULL);
+/*!40000 ALTER TABLE `page` ENABLE KEYS */;
+";
+let titles: Vec<(PageNamespace, PageTitle)> = iterate_sql_insertions(sql)
+    .into_iter()
+    .map(|Page { namespace, title, .. }| (namespace, title))
+    .collect();
+assert_eq!(titles, [(PageNamespace(0), PageTitle("Main_Page".to_string()))]);
+```
+
+With the `utils` feature, it also re-exports `memory_map`, `assert_clean_finish`,
+`NamespaceMap`, and `NamespaceMapExt` from `utils`.
+*/
+
+pub use crate::{
+    field_types::{
+        CategoryId, ContentModel, FullPageTitle, LinkTargetId, NotNan, PageId, PageNamespace,
+        PageTitle, Timestamp, UserId,
+    },
+    from_sql::FromSql,
+    is_end_of_insertions, iterate_sql_insertions,
+    schemas::{
+        Category, CategoryLink, LanguageLink, LinkTarget, Page, PageLink, PageProperty, PrimaryKey,
+        Redirect, TemplateLink,
+    },
+    Error, FromSqlTuple, SqlInsertions,
+};
+
+#[cfg(feature = "utils")]
+pub use crate::utils::{assert_clean_finish, memory_map, NamespaceMap, NamespaceMapExt};
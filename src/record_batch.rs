@@ -0,0 +1,235 @@
+/*!
+Converts rows of some of the [`schemas`](crate::schemas) into Apache Arrow
+[`RecordBatch`]es, which can be handed off to DataFusion, Polars, or a Parquet writer.
+
+Id types are stored as their integer type, strings as `Utf8`,
+byte strings as `Binary`, and [`Timestamp`]s as `Timestamp(Second, None)`.
+Fields that are an [`Option`] are nullable.
+*/
+
+use std::sync::Arc;
+
+use arrow::{
+    array::{
+        ArrayRef, BinaryArray, BooleanArray, Float64Array, Int32Array, StringArray,
+        TimestampSecondArray, UInt32Array,
+    },
+    datatypes::{DataType, Field, Schema, TimeUnit},
+    record_batch::RecordBatch,
+};
+
+use crate::{
+    field_types::{ContentModel, Timestamp},
+    schemas::{CategoryLink, Page, Redirect},
+};
+
+/// Trait for schemas that can be converted into a [`RecordBatch`] by [`to_record_batch`].
+pub trait ToRecordBatch: Sized {
+    /// The Arrow schema, with a field for each field of the struct.
+    fn schema() -> Schema;
+
+    /// Converts the rows into one array for each field of [`schema`](Self::schema), in the same order.
+    fn columns(rows: &[Self]) -> Vec<ArrayRef>;
+}
+
+/// Converts the rows into a [`RecordBatch`] with the schema given by [`ToRecordBatch::schema`].
+pub fn to_record_batch<T: ToRecordBatch>(rows: &[T]) -> RecordBatch {
+    RecordBatch::try_new(Arc::new(T::schema()), T::columns(rows))
+        .expect("columns should match the schema")
+}
+
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Second, None)
+}
+
+fn timestamps<I: IntoIterator<Item = Option<Timestamp>>>(timestamps: I) -> ArrayRef {
+    Arc::new(
+        timestamps
+            .into_iter()
+            .map(|timestamp| timestamp.map(|timestamp| timestamp.and_utc().timestamp()))
+            .collect::<TimestampSecondArray>(),
+    )
+}
+
+impl<'input> ToRecordBatch for Page<'input> {
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("id", DataType::UInt32, false),
+            Field::new("namespace", DataType::Int32, false),
+            Field::new("title", DataType::Utf8, false),
+            Field::new("is_redirect", DataType::Boolean, false),
+            Field::new("is_new", DataType::Boolean, false),
+            Field::new("random", DataType::Float64, false),
+            Field::new("touched", timestamp_type(), false),
+            Field::new("links_updated", timestamp_type(), true),
+            Field::new("latest", DataType::UInt32, false),
+            Field::new("len", DataType::UInt32, false),
+            Field::new("content_model", DataType::Utf8, true),
+            Field::new("lang", DataType::Utf8, true),
+        ])
+    }
+
+    fn columns(rows: &[Self]) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(UInt32Array::from_iter_values(
+                rows.iter().map(|row| row.id.into_inner()),
+            )),
+            Arc::new(Int32Array::from_iter_values(
+                rows.iter().map(|row| row.namespace.into_inner()),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|row| &row.title.0),
+            )),
+            Arc::new(
+                rows.iter()
+                    .map(|row| Some(row.is_redirect))
+                    .collect::<BooleanArray>(),
+            ),
+            Arc::new(
+                rows.iter()
+                    .map(|row| Some(row.is_new))
+                    .collect::<BooleanArray>(),
+            ),
+            Arc::new(Float64Array::from_iter_values(
+                rows.iter().map(|row| row.random.into_inner()),
+            )),
+            timestamps(rows.iter().map(|row| Some(row.touched))),
+            timestamps(rows.iter().map(|row| row.links_updated)),
+            Arc::new(UInt32Array::from_iter_values(
+                rows.iter().map(|row| row.latest),
+            )),
+            Arc::new(UInt32Array::from_iter_values(
+                rows.iter().map(|row| row.len),
+            )),
+            Arc::new(
+                rows.iter()
+                    .map(|row| row.content_model.as_ref().map(ContentModel::as_str))
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(rows.iter().map(|row| row.lang).collect::<StringArray>()),
+        ]
+    }
+}
+
+impl<'input> ToRecordBatch for Redirect<'input> {
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("from", DataType::UInt32, false),
+            Field::new("namespace", DataType::Int32, false),
+            Field::new("title", DataType::Utf8, false),
+            Field::new("interwiki", DataType::Utf8, true),
+            Field::new("fragment", DataType::Utf8, true),
+        ])
+    }
+
+    fn columns(rows: &[Self]) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(UInt32Array::from_iter_values(
+                rows.iter().map(|row| row.from.into_inner()),
+            )),
+            Arc::new(Int32Array::from_iter_values(
+                rows.iter().map(|row| row.namespace.into_inner()),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|row| &row.title.0),
+            )),
+            Arc::new(
+                rows.iter()
+                    .map(|row| row.interwiki)
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(
+                rows.iter()
+                    .map(|row| row.fragment.as_deref())
+                    .collect::<StringArray>(),
+            ),
+        ]
+    }
+}
+
+impl<'input> ToRecordBatch for CategoryLink<'input> {
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("from", DataType::UInt32, false),
+            Field::new("to", DataType::Utf8, false),
+            Field::new("sortkey", DataType::Binary, false),
+            Field::new("timestamp", timestamp_type(), false),
+            Field::new("sortkey_prefix", DataType::Binary, false),
+            Field::new("collation", DataType::Utf8, false),
+            Field::new("type", DataType::Utf8, false),
+        ])
+    }
+
+    fn columns(rows: &[Self]) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(UInt32Array::from_iter_values(
+                rows.iter().map(|row| row.from.into_inner()),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|row| &row.to.0),
+            )),
+            Arc::new(BinaryArray::from_iter_values(
+                rows.iter().map(|row| &row.sortkey),
+            )),
+            timestamps(rows.iter().map(|row| Some(row.timestamp))),
+            Arc::new(BinaryArray::from_iter_values(
+                rows.iter().map(|row| &row.sortkey_prefix),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|row| &row.collation),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|row| row.r#type.as_str()),
+            )),
+        ]
+    }
+}
+
+#[test]
+fn test_to_record_batch() {
+    use crate::iterate_sql_insertions;
+    use arrow::array::Array;
+
+    let sql =
+        br"INSERT INTO `redirect` VALUES (1,0,'Target','',''),(2,10,'Other_target',NULL,'Section');
+/*!40000 ALTER TABLE `redirect` ENABLE KEYS */;
+";
+    let redirects: Vec<Redirect> = (&mut iterate_sql_insertions(sql)).collect();
+    let batch = to_record_batch(&redirects);
+    assert_eq!(batch.num_rows(), 2);
+    assert_eq!(batch.schema().as_ref(), &Redirect::schema());
+    let namespaces = batch
+        .column(1)
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!(namespaces.values(), &[0, 10]);
+    let interwikis = batch
+        .column(3)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(interwikis.value(0), "");
+    assert!(interwikis.is_null(1));
+
+    let sql = br"INSERT INTO `categorylinks` VALUES (1,'Nouns','NOUN','2023-01-01 00:00:00','','uca-default-u-kn','page');
+/*!40000 ALTER TABLE `categorylinks` ENABLE KEYS */;
+";
+    let links: Vec<CategoryLink> = (&mut iterate_sql_insertions(sql)).collect();
+    let batch = to_record_batch(&links);
+    let timestamps = batch
+        .column(3)
+        .as_any()
+        .downcast_ref::<TimestampSecondArray>()
+        .unwrap();
+    assert_eq!(timestamps.value(0), 1_672_531_200);
+    assert_eq!(batch.schema().field(6).data_type(), &DataType::Utf8);
+
+    let sql = br"INSERT INTO `page` VALUES (1,0,'A',0,1,0.5,'20230101000000',NULL,10,100,'wikitext',NULL);
+/*!40000 ALTER TABLE `page` ENABLE KEYS */;
+";
+    let pages: Vec<Page> = (&mut iterate_sql_insertions(sql)).collect();
+    let batch = to_record_batch(&pages);
+    assert_eq!(batch.num_columns(), 12);
+    assert!(batch.column(7).is_null(0));
+}
@@ -17,13 +17,19 @@ use crate::{
     field_types::{
         ActorId, CategoryId, ChangeTagDefinitionId, ChangeTagId, CommentId, ContentModel, Expiry,
         ExternalLinkId, FullPageTitle, LinkTargetId, LogId, MajorMime, MediaType, MinorMime,
-        NotNan, PageAction, PageCount, PageId, PageNamespace, PageRestrictionId, PageTitle,
-        PageType, ProtectionLevel, RecentChangeId, RevisionId, Sha1, Timestamp, UserGroup, UserId,
+        NaiveDateTime, NotNan, PageAction, PageCount, PageId, PageNamespace, PageRestrictionId,
+        PageTitle, PageType, ProtectionLevel, RecentChangeId, RevisionId, Sha1, Timestamp,
+        ToOwnedField, ToTsvField, UserGroup, UserId,
     },
-    from_sql::{FromSql, IResult},
+    from_sql::{FromSql, IResult, SqlDialect},
     FromSqlTuple,
 };
 
+use std::{
+    borrow::Cow,
+    fmt::{Debug, Write as _},
+};
+
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
 
@@ -76,6 +82,7 @@ macro_rules! database_table_doc {
 
 macro_rules! impl_row_from_sql {
     (
+        $(#[$struct_meta:meta])*
         $table_name:ident $(: $page:literal)?
         $output_type:ident {
             $(
@@ -87,8 +94,10 @@ macro_rules! impl_row_from_sql {
     ) => {
         with_doc_comment! {
             database_table_doc!($table_name $(, $page)?),
+            $(#[$struct_meta])*
             #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
             #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+            #[cfg_attr(feature = "strict-deserialization", serde(deny_unknown_fields))]
             pub struct $output_type {
                 $(
                     $(#[$field_meta])*
@@ -98,6 +107,13 @@ macro_rules! impl_row_from_sql {
 
             impl<'input> FromSqlTuple<'input> for $output_type {
                 fn from_sql_tuple(s: &'input [u8]) -> IResult<'input, Self> {
+                    Self::from_sql_tuple_with_dialect(s, &SqlDialect::MYSQL)
+                }
+
+                fn from_sql_tuple_with_dialect(
+                    s: &'input [u8],
+                    dialect: &SqlDialect,
+                ) -> IResult<'input, Self> {
                     let fields = cut(
                         map(
                             tuple((
@@ -109,7 +125,7 @@ macro_rules! impl_row_from_sql {
                                                 stringify!($field_name),
                                                 "”"
                                             ),
-                                            <$type_name>::from_sql,
+                                            |s| <$type_name>::from_sql_with_dialect(s, dialect),
                                         ),
                                         opt(char(','))
                                     ),
@@ -132,30 +148,60 @@ macro_rules! impl_row_from_sql {
                     )(s)
                 }
             }
+
+            impl $output_type {
+                /// Formats the row with the name and [`Debug`](std::fmt::Debug) representation
+                /// of each field on its own line, with the values aligned.
+                pub fn pretty(&self) -> String {
+                    pretty_row(&[$((stringify!($field_name), &self.$field_name),)+])
+                }
+
+                /// Formats the fields as a line of tab-separated values, without a line break,
+                /// as described in [`ToTsvField`].
+                pub fn to_tsv_line(&self) -> String {
+                    tsv_line(&[$(&self.$field_name,)+])
+                }
+            }
         }
     };
     (
+        $(#[$struct_meta:meta])*
         $table_name:ident $(: $page:literal)?
-        $output_type:ident<$life:lifetime> {
+        $output_type:ident<$life:lifetime> => $owned_type:ident {
             $(
                 $(#[$field_meta:meta])*
-                $field_name:ident: $type_name:ty,
+                $field_name:ident: $type_name:ty $(=> $owned_field_type:ty)?,
             )+
         }
     ) => {
         with_doc_comment! {
             database_table_doc!($table_name $(, $page)?),
+            $(#[$struct_meta])*
             #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
             #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+            #[cfg_attr(feature = "strict-deserialization", serde(deny_unknown_fields))]
             pub struct $output_type<$life> {
                 $(
                     $(#[$field_meta])*
+                    // Fields that borrow from the input are the ones with an owned type after `=>`.
+                    // The first attribute is always removed and only makes the repetition depend on it.
+                    $(
+                        #[cfg_attr(any(), doc = stringify!($owned_field_type))]
+                        #[cfg_attr(feature = "serialization", serde(borrow))]
+                    )?
                     pub $field_name: $type_name
                 ),+
             }
 
             impl<$life> FromSqlTuple<$life> for $output_type<$life> {
                 fn from_sql_tuple(s: &$life [u8]) -> IResult<$life, Self> {
+                    Self::from_sql_tuple_with_dialect(s, &SqlDialect::MYSQL)
+                }
+
+                fn from_sql_tuple_with_dialect(
+                    s: &$life [u8],
+                    dialect: &SqlDialect,
+                ) -> IResult<$life, Self> {
                     let fields = cut(
                         map(
                             tuple((
@@ -167,7 +213,7 @@ macro_rules! impl_row_from_sql {
                                                 stringify!($field_name),
                                                 "”"
                                             ),
-                                            <$type_name>::from_sql,
+                                            |s| <$type_name>::from_sql_with_dialect(s, dialect),
                                         ),
                                         opt(char(','))
                                     ),
@@ -190,16 +236,267 @@ macro_rules! impl_row_from_sql {
                     )(s)
                 }
             }
+
+            impl<$life> $output_type<$life> {
+                /// Formats the row with the name and [`Debug`](std::fmt::Debug) representation
+                /// of each field on its own line, with the values aligned.
+                pub fn pretty(&self) -> String {
+                    pretty_row(&[$((stringify!($field_name), &self.$field_name),)+])
+                }
+
+                /// Formats the fields as a line of tab-separated values, without a line break,
+                /// as described in [`ToTsvField`].
+                pub fn to_tsv_line(&self) -> String {
+                    tsv_line(&[$(&self.$field_name,)+])
+                }
+            }
         }
+
+        #[doc = concat!(
+            "A version of [`", stringify!($output_type), "`] that owns its fields, ",
+            "so that it can be kept after the input is dropped or sent to another thread. ",
+            "Created by [`", stringify!($output_type), "::to_owned_row`]. ",
+            "See [`ToOwnedField`] for the types of the fields."
+        )]
+        #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+        #[cfg_attr(feature = "strict-deserialization", serde(deny_unknown_fields))]
+        pub struct $owned_type {
+            $(
+                $(#[$field_meta])*
+                pub $field_name: owned_field_type!($type_name $(=> $owned_field_type)?),
+            )+
+        }
+
+        impl<$life> $output_type<$life> {
+            #[doc = concat!(
+                "Copies the fields that borrow from the input to make a [`",
+                stringify!($owned_type), "`]."
+            )]
+            pub fn to_owned_row(&self) -> $owned_type {
+                $owned_type {
+                    $($field_name: self.$field_name.to_owned_field(),)+
+                }
+            }
+        }
+
+        /// Parses the row and copies the fields that borrow from the input,
+        /// for when rows must outlive the input.
+        #[cfg_attr(
+            feature = "utils",
+            doc = "They can then be sent to another thread, as in [`spawn_parser`](crate::utils::spawn_parser)."
+        )]
+        impl<$life> FromSqlTuple<$life> for $owned_type {
+            fn from_sql_tuple(s: &$life [u8]) -> IResult<$life, Self> {
+                map(<$output_type>::from_sql_tuple, |row| row.to_owned_row())(s)
+            }
+
+            fn from_sql_tuple_with_dialect(
+                s: &$life [u8],
+                dialect: &SqlDialect,
+            ) -> IResult<$life, Self> {
+                map(
+                    |s| <$output_type>::from_sql_tuple_with_dialect(s, dialect),
+                    |row| row.to_owned_row(),
+                )(s)
+            }
+        }
+    };
+}
+
+/// The type of a field in the owned struct generated by `impl_row_from_sql!`:
+/// the type given after `=>` for fields that borrow from the input,
+/// otherwise the type of the field itself.
+macro_rules! owned_field_type {
+    ($type_name:ty) => {
+        $type_name
     };
+    ($type_name:ty => $owned_field_type:ty) => {
+        $owned_field_type
+    };
+}
+
+/// Formats fields for the `pretty` method generated by `impl_row_from_sql!`.
+fn pretty_row(fields: &[(&str, &dyn Debug)]) -> String {
+    let fields: Vec<_> = fields
+        .iter()
+        .map(|&(name, value)| (name.trim_start_matches("r#"), value))
+        .collect();
+    let width = fields.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    let mut pretty = String::new();
+    for (name, value) in fields {
+        writeln!(pretty, "{:width$}  {:?}", name, value, width = width)
+            .expect("writing to a String should succeed");
+    }
+    pretty
+}
+
+/// Formats fields for the `to_tsv_line` method generated by `impl_row_from_sql!`.
+fn tsv_line(fields: &[&dyn ToTsvField]) -> String {
+    let mut line = String::new();
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            line.push('\t');
+        }
+        field.write_tsv_field(&mut line);
+    }
+    line
+}
+
+/**
+Implemented by the schemas for tables whose primary key is a single column,
+so that rows can be paired with their keys by [`SqlInsertions::with_keys`](crate::SqlInsertions::with_keys).
+*/
+pub trait PrimaryKey {
+    /// The type of the primary key.
+    type Key: Clone;
+
+    /// Returns the value of the field that represents the primary key.
+    fn primary_key(&self) -> Self::Key;
+}
+
+macro_rules! impl_primary_key {
+    (
+        $(
+            $output_type:ident $(<$life:lifetime>)? => $field_name:ident: $key:ty
+        ),+
+        $(,)?
+    ) => {
+        $(
+            impl$(<$life>)? PrimaryKey for $output_type$(<$life>)? {
+                type Key = $key;
+
+                fn primary_key(&self) -> $key {
+                    Clone::clone(&self.$field_name)
+                }
+            }
+        )+
+    };
+}
+
+impl_primary_key! {
+    Category => id: CategoryId,
+    ChangeTag => id: ChangeTagId,
+    ChangeTagDefinition => id: ChangeTagDefinitionId,
+    ExternalLink => id: ExternalLinkId,
+    ExternalLinkLegacy => id: ExternalLinkId,
+    GeoTag<'input> => id: u32,
+    Image<'input> => name: PageTitle,
+    Job<'input> => id: u32,
+    LinkTarget => id: LinkTargetId,
+    PageRestriction<'input> => id: PageRestrictionId,
+    Page<'input> => id: PageId,
+    Redirect<'input> => from: PageId,
+    Site<'input> => id: u32,
+    SiteStats => row_id: u32,
+    WikibaseClientEntityUsage<'input> => row_id: u64,
+    WbtText => id: u64,
+    WbtTextInLang<'input> => id: u64,
+    WbtTermInLang => id: u64,
+    WbtType<'input> => id: u32,
+    UploadStash<'input> => id: u32,
+}
+
+#[test]
+fn test_with_keys() {
+    use crate::iterate_sql_insertions;
+
+    let sql = br"INSERT INTO `page` VALUES (1,0,'A',0,0,0.5,'20230102030405',NULL,10,20,'wikitext',NULL),(3,10,'B',1,0,0.25,'20230102030405',NULL,11,21,'wikitext',NULL);";
+    let pages: Vec<_> = iterate_sql_insertions::<Page>(sql)
+        .with_keys()
+        .map(|(id, page)| (id, page.title))
+        .collect();
+    assert_eq!(
+        pages,
+        [
+            (PageId(1), PageTitle("A".to_string())),
+            (PageId(3), PageTitle("B".to_string())),
+        ]
+    );
+
+    let sql = br"INSERT INTO `category` VALUES (5,'Nouns',3,0,0),(8,'Verbs',2,1,0);";
+    let categories: Vec<_> = iterate_sql_insertions::<Category>(sql)
+        .with_keys()
+        .map(|(id, category)| (id, category.pages))
+        .collect();
+    assert_eq!(
+        categories,
+        [(CategoryId(5), PageCount(3)), (CategoryId(8), PageCount(2))]
+    );
 }
 
 impl_row_from_sql! {
     babel: "Extension:Babel/babel_table"
-    Babel<'input> {
+    Babel<'input> => BabelOwned {
         user: UserId,
-        lang: &'input str,
-        level: &'input str,
+        lang: &'input str => String,
+        level: &'input str => String,
+    }
+}
+
+impl_row_from_sql! {
+    bot_passwords
+    BotPassword<'input> => BotPasswordOwned {
+        user: UserId,
+        app_id: &'input str => String,
+        /// The hashed password, which is only present in private dumps.
+        #[cfg_attr(feature = "serialization", serde(with = "crate::field_types::byte_string"))]
+        password: Vec<u8>,
+        token: &'input str => String,
+        /// JSON object describing the IP address restrictions.
+        #[cfg_attr(feature = "serialization", serde(with = "crate::field_types::byte_string"))]
+        restrictions: Vec<u8>,
+        /// JSON array of the grants given to the bot password.
+        #[cfg_attr(feature = "serialization", serde(with = "crate::field_types::byte_string"))]
+        grants: Vec<u8>,
+    }
+}
+
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+impl<'input> BotPassword<'input> {
+    /// Parses [`restrictions`](Self::restrictions) as JSON.
+    /// Returns `None` if it is not valid JSON.
+    pub fn restrictions_json(&self) -> Option<serde_json::Value> {
+        serde_json::from_slice(&self.restrictions).ok()
+    }
+
+    /// Parses [`grants`](Self::grants) as JSON.
+    /// Returns `None` if it is not valid JSON.
+    pub fn grants_json(&self) -> Option<serde_json::Value> {
+        serde_json::from_slice(&self.grants).ok()
+    }
+}
+
+#[test]
+fn test_bot_password() {
+    use bstr::B;
+    let tuple = br#"(42,'archiver','','0123456789abcdef0123456789abcdef','{\"IPAddresses\":[\"0.0.0.0/0\",\"::/0\"]}','[\"basic\",\"highvolume\"]')"#;
+    assert_eq!(
+        BotPassword::from_sql_tuple(tuple),
+        Ok((
+            B(""),
+            BotPassword {
+                user: UserId(42),
+                app_id: "archiver",
+                password: Vec::new(),
+                token: "0123456789abcdef0123456789abcdef",
+                restrictions: br#"{"IPAddresses":["0.0.0.0/0","::/0"]}"#.to_vec(),
+                grants: br#"["basic","highvolume"]"#.to_vec(),
+            }
+        ))
+    );
+    #[cfg(feature = "json")]
+    {
+        let (_, bot_password) = BotPassword::from_sql_tuple(tuple).unwrap();
+        assert_eq!(
+            bot_password.restrictions_json(),
+            Some(serde_json::json!({ "IPAddresses": ["0.0.0.0/0", "::/0"] }))
+        );
+        assert_eq!(
+            bot_password.grants_json(),
+            Some(serde_json::json!(["basic", "highvolume"]))
+        );
     }
 }
 
@@ -216,23 +513,172 @@ impl_row_from_sql! {
 
 impl_row_from_sql! {
     categorylinks
-    CategoryLink {
+    CategoryLink<'input> => CategoryLinkOwned {
         from: PageId,
         to: PageTitle,
         /// Can be truncated in the middle of a UTF-8 sequence,
         /// so cannot be represented as a `String`.
+        #[cfg_attr(feature = "serialization", serde(with = "crate::field_types::byte_string"))]
         sortkey: Vec<u8>,
         timestamp: Timestamp,
         /// Values added after
         /// [this change](https://gerrit.wikimedia.org/r/449280),
         /// should be valid UTF-8, but older values may be invalid if they have
         /// been truncated in the middle of a multi-byte sequence.
+        #[cfg_attr(feature = "serialization", serde(with = "crate::field_types::byte_string"))]
         sortkey_prefix: Vec<u8>,
         collation: String,
-        r#type: PageType,
+        r#type: PageType<'input> => PageType<'static>,
+    }
+}
+
+#[test]
+fn test_to_tsv_line() {
+    let (_, link) = CategoryLink::from_sql_tuple(
+        b"(1,'Nouns','A\\tB\\n\xff','2023-01-01 00:00:00','','uca-default-u-kn','page')",
+    )
+    .unwrap();
+    assert_eq!(
+        link.to_tsv_line(),
+        "1\tNouns\tA\\tB\\n\u{FFFD}\t2023-01-01 00:00:00\t\tuca-default-u-kn\tpage"
+    );
+}
+
+impl<'input> CategoryLink<'input> {
+    /// Converts [`sortkey`](Self::sortkey) to a string for display,
+    /// replacing a UTF-8 sequence truncated at the end, or any other invalid UTF-8,
+    /// with `U+FFFD REPLACEMENT CHARACTER`.
+    pub fn sortkey_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.sortkey)
+    }
+
+    /// Converts [`sortkey_prefix`](Self::sortkey_prefix) to a string for display
+    /// in the same way as [`sortkey_lossy`](Self::sortkey_lossy).
+    pub fn sortkey_prefix_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.sortkey_prefix)
     }
 }
 
+#[test]
+fn test_sortkey_lossy() {
+    // "ÉCOLE" truncated after the first byte of "É".
+    let tuple = b"(1,'French_nouns','\xc3','2023-01-01 00:00:00','\xc3\x89COLE','uca-fr','page')";
+    let (_, link) = CategoryLink::from_sql_tuple(tuple).unwrap();
+    assert_eq!(link.sortkey_lossy(), "\u{FFFD}");
+    assert!(matches!(
+        link.sortkey_prefix_lossy(),
+        Cow::Borrowed("ÉCOLE")
+    ));
+}
+
+impl_row_from_sql! {
+    /// This is the layout used before MediaWiki 1.17 (2011),
+    /// which added the `cl_sortkey_prefix`, `cl_collation`, and `cl_type` fields,
+    /// so it can parse historical dumps. For newer dumps, use [`CategoryLink`].
+    /// See [`CategoryLinkLayout`].
+    categorylinks
+    CategoryLinkLegacy {
+        from: PageId,
+        to: PageTitle,
+        #[cfg_attr(feature = "serialization", serde(with = "crate::field_types::byte_string"))]
+        sortkey: Vec<u8>,
+        timestamp: Timestamp,
+    }
+}
+
+#[test]
+fn test_category_link_legacy() {
+    use bstr::B;
+    let tuple = br"(4107,'English_nouns','Dictionary','2008-03-14 18:32:07')";
+    assert_eq!(
+        CategoryLinkLegacy::from_sql_tuple(tuple),
+        Ok((
+            B(""),
+            CategoryLinkLegacy {
+                from: PageId(4107),
+                to: PageTitle("English_nouns".to_string()),
+                sortkey: b"Dictionary".to_vec(),
+                timestamp: Timestamp(
+                    chrono::NaiveDate::from_ymd_opt(2008, 3, 14)
+                        .unwrap()
+                        .and_hms_opt(18, 32, 7)
+                        .unwrap()
+                ),
+            }
+        ))
+    );
+    assert!(CategoryLink::from_sql_tuple(tuple).is_err());
+}
+
+/// The layout of the columns in a `categorylinks.sql` dump,
+/// determining whether to parse it with [`CategoryLink`] or [`CategoryLinkLegacy`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum CategoryLinkLayout {
+    /// `cl_from`, `cl_to`, `cl_sortkey`, `cl_timestamp`, `cl_sortkey_prefix`, `cl_collation`, `cl_type`,
+    /// parsed by [`CategoryLink`].
+    Collation,
+    /// `cl_from`, `cl_to`, `cl_sortkey`, `cl_timestamp`, parsed by [`CategoryLinkLegacy`].
+    Legacy,
+}
+
+impl CategoryLinkLayout {
+    /// Detects the layout from the `CREATE TABLE` statement at the beginning of `categorylinks.sql`
+    /// using [`parse_create_table`](crate::parse_create_table).
+    /// Returns `None` if there is no `CREATE TABLE` statement or the columns match neither layout.
+    pub fn detect(sql: &[u8]) -> Option<Self> {
+        let columns = crate::parse_create_table(sql).ok()?;
+        let names: Vec<_> = columns.iter().map(|(name, _)| name.as_str()).collect();
+        match names.as_slice() {
+            ["cl_from", "cl_to", "cl_sortkey", "cl_timestamp", "cl_sortkey_prefix", "cl_collation", "cl_type"] => {
+                Some(CategoryLinkLayout::Collation)
+            }
+            ["cl_from", "cl_to", "cl_sortkey", "cl_timestamp"] => Some(CategoryLinkLayout::Legacy),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_category_link_layouts() {
+    let legacy_sql = b"CREATE TABLE `categorylinks` (
+  `cl_from` int(8) unsigned NOT NULL DEFAULT '0',
+  `cl_to` varchar(255) binary NOT NULL DEFAULT '',
+  `cl_sortkey` varchar(70) binary NOT NULL DEFAULT '',
+  `cl_timestamp` timestamp NOT NULL
+);
+";
+    assert_eq!(
+        CategoryLinkLayout::detect(legacy_sql),
+        Some(CategoryLinkLayout::Legacy)
+    );
+
+    let sql = b"CREATE TABLE `categorylinks` (
+  `cl_from` int(8) unsigned NOT NULL DEFAULT 0,
+  `cl_to` varbinary(255) NOT NULL DEFAULT '',
+  `cl_sortkey` varbinary(230) NOT NULL DEFAULT '',
+  `cl_timestamp` timestamp NOT NULL DEFAULT current_timestamp() ON UPDATE current_timestamp(),
+  `cl_sortkey_prefix` varbinary(255) NOT NULL DEFAULT '',
+  `cl_collation` varbinary(32) NOT NULL DEFAULT '',
+  `cl_type` enum('page','subcat','file') NOT NULL DEFAULT 'page',
+  PRIMARY KEY (`cl_from`,`cl_to`)
+) ENGINE=InnoDB DEFAULT CHARSET=binary;
+";
+    assert_eq!(
+        CategoryLinkLayout::detect(sql),
+        Some(CategoryLinkLayout::Collation)
+    );
+    assert_eq!(CategoryLinkLayout::detect(b"INSERT INTO"), None);
+}
+
+#[test]
+fn test_category_link_unknown_type() {
+    let tuple = br"(1,'Audio_files','A','2023-01-01 00:00:00','','uca-default-u-kn','media')";
+    let (_, link) = CategoryLink::from_sql_tuple(tuple).unwrap();
+    assert_eq!(link.r#type, PageType::Other("media".into()));
+    let owned = link.to_owned_row();
+    assert_eq!(owned.r#type, PageType::Other("media".into()));
+}
+
 impl_row_from_sql! {
     change_tag
     ChangeTag {
@@ -245,6 +691,37 @@ impl_row_from_sql! {
     }
 }
 
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+impl ChangeTag {
+    /// Parses [`params`](Self::params) as JSON.
+    /// Returns `None` if there are no parameters or they are not valid JSON.
+    pub fn params_json(&self) -> Option<serde_json::Value> {
+        serde_json::from_str(self.params.as_deref()?).ok()
+    }
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_change_tag_params_json() {
+    let tuple = br#"(1,10,NULL,20,'{\"reverted\":true,\"newestRevertedRevId\":19}',3)"#;
+    let (_, tag) = ChangeTag::from_sql_tuple(tuple).unwrap();
+    assert_eq!(
+        tag.params_json(),
+        Some(serde_json::json!({ "reverted": true, "newestRevertedRevId": 19 }))
+    );
+    let tuple = br"(2,11,NULL,21,NULL,3)";
+    assert_eq!(
+        ChangeTag::from_sql_tuple(tuple).unwrap().1.params_json(),
+        None
+    );
+    let tuple = br"(3,12,NULL,22,'not JSON',3)";
+    assert_eq!(
+        ChangeTag::from_sql_tuple(tuple).unwrap().1.params_json(),
+        None
+    );
+}
+
 impl_row_from_sql! {
     change_tag_def
     ChangeTagDefinition {
@@ -256,39 +733,328 @@ impl_row_from_sql! {
 }
 
 impl_row_from_sql! {
+    /// This is the layout used since MediaWiki 1.41 (2024),
+    /// in which the URL is split into `el_to_domain_index` and `el_to_path`
+    /// and `el_to`, `el_index`, and `el_index_60` were dropped.
+    /// For older dumps, use [`ExternalLinkLegacy`]. See [`ExternalLinkLayout`].
     externallinks
     ExternalLink {
+        id: ExternalLinkId,
+        from: PageId,
+        to_domain_index: String,
+        to_path: Option<String>,
+    }
+}
+
+impl_row_from_sql! {
+    /// This is the layout used in dumps from before MediaWiki 1.41 (2024),
+    /// with the full URL in `el_to`.
+    /// For newer dumps, use [`ExternalLink`]. See [`ExternalLinkLayout`].
+    externallinks
+    ExternalLinkLegacy {
         id: ExternalLinkId,
         from: PageId,
         to: String,
+        #[cfg_attr(feature = "serialization", serde(with = "crate::field_types::byte_string"))]
         index: Vec<u8>,
+        #[cfg_attr(feature = "serialization", serde(with = "crate::field_types::byte_string"))]
         index_60: Vec<u8>,
     }
 }
 
+impl ExternalLink {
+    /**
+    Reconstructs the URL from [`to_domain_index`](Self::to_domain_index)
+    and [`to_path`](Self::to_path), reversing what MediaWiki's `LinkFilter` does
+    to make the domain index: the labels of the host name are put back in order,
+    IP addresses lose their `V4.` or `V6.` prefix, and the user name in a `mailto:` link
+    is moved back in front of the domain.
+
+    User names and passwords in other URLs are not stored in the index, so they can't be recovered,
+    and IPv6 addresses are returned with all eight groups rather than in their shortest form.
+    */
+    pub fn url(&self) -> String {
+        let mut url = String::with_capacity(
+            self.to_domain_index.len() + self.to_path.as_ref().map_or(0, String::len),
+        );
+        push_domain_index(&mut url, &self.to_domain_index, push_host);
+        if let Some(path) = &self.to_path {
+            url.push_str(path);
+        }
+        url
+    }
+}
+
+/// Pushes the URL represented by a domain index, like `https://org.wikipedia.en.`
+/// or the legacy `el_index`, which is followed by the path,
+/// using `push_host` to push the host name.
+pub(crate) fn push_domain_index(
+    url: &mut String,
+    index: &str,
+    push_host: impl Fn(&mut String, &str),
+) {
+    let (scheme, rest) = match index.find("://") {
+        Some(pos) => index.split_at(pos + "://".len()),
+        None => match index.find(':') {
+            Some(pos) => index.split_at(pos + ":".len()),
+            None => ("", index),
+        },
+    };
+    url.push_str(scheme);
+    if scheme == "mailto:" {
+        match rest.split_once('@') {
+            Some((domain, user)) => {
+                url.push_str(user);
+                url.push('@');
+                push_host(url, domain);
+            }
+            None => url.push_str(rest),
+        }
+    } else {
+        let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        let (host, port) = match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        };
+        push_host(url, host);
+        if let Some(port) = port {
+            url.push(':');
+            url.push_str(port);
+        }
+        url.push_str(path);
+    }
+}
+
+/// Pushes the host name represented by a host in a domain index,
+/// like `org.wikipedia.en.` for `en.wikipedia.org`.
+pub(crate) fn push_host(url: &mut String, host: &str) {
+    let host = host.strip_suffix('.').unwrap_or(host);
+    if let Some(ipv4) = host.strip_prefix("V4.") {
+        url.push_str(ipv4);
+    } else if let Some(ipv6) = host.strip_prefix("V6.") {
+        url.push('[');
+        url.push_str(&ipv6.replace('.', ":"));
+        url.push(']');
+    } else {
+        for (i, label) in host.rsplit('.').enumerate() {
+            if i > 0 {
+                url.push('.');
+            }
+            url.push_str(label);
+        }
+    }
+}
+
+#[test]
+fn test_external_link_url() {
+    let url = |to_domain_index: &str, to_path: Option<&str>| {
+        ExternalLink {
+            id: ExternalLinkId(1),
+            from: PageId(1),
+            to_domain_index: to_domain_index.to_string(),
+            to_path: to_path.map(String::from),
+        }
+        .url()
+    };
+    assert_eq!(
+        url("https://org.wikipedia.en.", Some("/wiki/Main_Page")),
+        "https://en.wikipedia.org/wiki/Main_Page"
+    );
+    assert_eq!(
+        url("http://com.example.", Some("/?q=a.b:c")),
+        "http://example.com/?q=a.b:c"
+    );
+    assert_eq!(
+        url("http://com.example.www.:8080", Some("/path")),
+        "http://www.example.com:8080/path"
+    );
+    assert_eq!(url("https://org.example.", None), "https://example.org");
+    assert_eq!(
+        url("http://V4.127.0.0.1.:80", Some("/")),
+        "http://127.0.0.1:80/"
+    );
+    assert_eq!(
+        url("https://V6.2001.DB8.0.0.0.0.0.1.", Some("/")),
+        "https://[2001:DB8:0:0:0:0:0:1]/"
+    );
+    assert_eq!(
+        url("mailto:org.example.@user", None),
+        "mailto:user@example.org"
+    );
+}
+
+/// The layout of the columns in an `externallinks.sql` dump,
+/// determining whether to parse it with [`ExternalLink`] or [`ExternalLinkLegacy`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ExternalLinkLayout {
+    /// `el_id`, `el_from`, `el_to_domain_index`, `el_to_path`, parsed by [`ExternalLink`].
+    DomainIndex,
+    /// `el_id`, `el_from`, `el_to`, `el_index`, `el_index_60`, parsed by [`ExternalLinkLegacy`].
+    Legacy,
+}
+
+impl ExternalLinkLayout {
+    /// Detects the layout from the `CREATE TABLE` statement at the beginning of `externallinks.sql`
+    /// using [`parse_create_table`](crate::parse_create_table).
+    /// Returns `None` if there is no `CREATE TABLE` statement or the columns match neither layout,
+    /// as in dumps made while the migration was in progress.
+    pub fn detect(sql: &[u8]) -> Option<Self> {
+        let columns = crate::parse_create_table(sql).ok()?;
+        let names: Vec<_> = columns.iter().map(|(name, _)| name.as_str()).collect();
+        match names.as_slice() {
+            ["el_id", "el_from", "el_to_domain_index", "el_to_path"] => {
+                Some(ExternalLinkLayout::DomainIndex)
+            }
+            ["el_id", "el_from", "el_to", "el_index", "el_index_60"] => {
+                Some(ExternalLinkLayout::Legacy)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_external_link_layouts() {
+    use crate::iterate_sql_insertions;
+
+    let legacy_sql = br"CREATE TABLE `externallinks` (
+  `el_id` int(10) unsigned NOT NULL AUTO_INCREMENT,
+  `el_from` int(8) unsigned NOT NULL DEFAULT 0,
+  `el_to` blob NOT NULL,
+  `el_index` blob NOT NULL,
+  `el_index_60` varbinary(60) NOT NULL,
+  PRIMARY KEY (`el_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=binary;
+INSERT INTO `externallinks` VALUES (1,10,'https://www.example.org/wiki?a=b','https://org.example.www./wiki?a=b','https://org.example.www./wiki?a=b');
+/*!40000 ALTER TABLE `externallinks` ENABLE KEYS */;
+";
+    assert_eq!(
+        ExternalLinkLayout::detect(legacy_sql),
+        Some(ExternalLinkLayout::Legacy)
+    );
+    let links: Vec<_> = iterate_sql_insertions::<ExternalLinkLegacy>(legacy_sql).collect();
+    assert_eq!(
+        links,
+        [ExternalLinkLegacy {
+            id: ExternalLinkId(1),
+            from: PageId(10),
+            to: "https://www.example.org/wiki?a=b".to_string(),
+            index: b"https://org.example.www./wiki?a=b".to_vec(),
+            index_60: b"https://org.example.www./wiki?a=b".to_vec(),
+        }]
+    );
+
+    let sql = br"CREATE TABLE `externallinks` (
+  `el_id` int(10) unsigned NOT NULL AUTO_INCREMENT,
+  `el_from` int(8) unsigned NOT NULL DEFAULT 0,
+  `el_to_domain_index` varbinary(255) NOT NULL DEFAULT '',
+  `el_to_path` blob DEFAULT NULL,
+  PRIMARY KEY (`el_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=binary;
+INSERT INTO `externallinks` VALUES (1,10,'https://org.example.www.','/wiki?a=b'),(2,11,'mailto:org.example.@user',NULL);
+/*!40000 ALTER TABLE `externallinks` ENABLE KEYS */;
+";
+    assert_eq!(
+        ExternalLinkLayout::detect(sql),
+        Some(ExternalLinkLayout::DomainIndex)
+    );
+    let links: Vec<_> = iterate_sql_insertions::<ExternalLink>(sql).collect();
+    assert_eq!(
+        links,
+        [
+            ExternalLink {
+                id: ExternalLinkId(1),
+                from: PageId(10),
+                to_domain_index: "https://org.example.www.".to_string(),
+                to_path: Some("/wiki?a=b".to_string()),
+            },
+            ExternalLink {
+                id: ExternalLinkId(2),
+                from: PageId(11),
+                to_domain_index: "mailto:org.example.@user".to_string(),
+                to_path: None,
+            },
+        ]
+    );
+}
+
+impl_row_from_sql! {
+    geo_tags: "Extension:GeoData/geo_tags_table"
+    GeoTag<'input> => GeoTagOwned {
+        id: u32,
+        page_id: PageId,
+        globe: &'input str => String,
+        primary: bool,
+        lat: Option<NotNan<f64>>,
+        lon: Option<NotNan<f64>>,
+        dim: Option<i32>,
+        r#type: Option<&'input str> => Option<String>,
+        name: Option<String>,
+        country: Option<&'input str> => Option<String>,
+        region: Option<&'input str> => Option<String>,
+    }
+}
+
+#[test]
+fn test_geo_tag() {
+    use bstr::B;
+    let tuple = br"(1517,645042,'earth',1,40.68925000,-74.04450000,100,'landmark',NULL,'US','NY')";
+    assert_eq!(
+        GeoTag::from_sql_tuple(tuple),
+        Ok((
+            B(""),
+            GeoTag {
+                id: 1517,
+                page_id: PageId(645042),
+                globe: "earth",
+                primary: true,
+                lat: Some(NotNan::new(40.68925).unwrap()),
+                lon: Some(NotNan::new(-74.0445).unwrap()),
+                dim: Some(100),
+                r#type: Some("landmark"),
+                name: None,
+                country: Some("US"),
+                region: Some("NY"),
+            }
+        ))
+    );
+}
+
 impl_row_from_sql! {
     image
-    Image<'input> {
+    Image<'input> => ImageOwned {
         name: PageTitle,
         size: u32,
         width: i32,
         height: i32,
         metadata: String,
         bits: i32,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        media_type: MediaType<'input>,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        major_mime: MajorMime<'input>,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        minor_mime: MinorMime<'input>,
+        media_type: MediaType<'input> => MediaType<'static>,
+        major_mime: MajorMime<'input> => MajorMime<'static>,
+        minor_mime: MinorMime<'input> => String,
         description_id: CommentId,
         actor: ActorId,
         timestamp: Timestamp,
-        sha1: Sha1<'input>,
+        sha1: Sha1<'input> => String,
+    }
+}
+
+impl_row_from_sql! {
+    /// This is the layout used since `il_target_id` was added in MediaWiki 1.45 (2025)
+    /// and `il_to` was dropped in favor of the `linktarget` table.
+    /// For older dumps, use [`ImageLink`]. See [`ImageLinkLayout`].
+    imagelinks
+    ImageLinkTarget {
+        from: PageId,
+        from_namespace: PageNamespace,
+        target: LinkTargetId,
     }
 }
 
 impl_row_from_sql! {
+    /// This is the layout used in dumps from before the migration to the `linktarget` table in 2025,
+    /// with the title of the file in the `imagelinks` table itself.
+    /// For newer dumps, use [`ImageLinkTarget`]. See [`ImageLinkLayout`].
     imagelinks
     ImageLink {
         from: PageId,
@@ -297,26 +1063,249 @@ impl_row_from_sql! {
     }
 }
 
+/// The layout of the columns in an `imagelinks.sql` dump,
+/// determining whether to parse it with [`ImageLinkTarget`] or [`ImageLink`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ImageLinkLayout {
+    /// `il_from`, `il_from_namespace`, `il_target_id`, parsed by [`ImageLinkTarget`].
+    LinkTarget,
+    /// `il_from`, `il_to`, `il_from_namespace`, parsed by [`ImageLink`].
+    Legacy,
+}
+
+impl ImageLinkLayout {
+    /// Detects the layout from the `CREATE TABLE` statement at the beginning of `imagelinks.sql`
+    /// using [`parse_create_table`](crate::parse_create_table).
+    /// Returns `None` if there is no `CREATE TABLE` statement or the columns match neither layout,
+    /// as in dumps made while the migration was in progress.
+    pub fn detect(sql: &[u8]) -> Option<Self> {
+        let columns = crate::parse_create_table(sql).ok()?;
+        let names: Vec<_> = columns.iter().map(|(name, _)| name.as_str()).collect();
+        match names.as_slice() {
+            ["il_from", "il_from_namespace", "il_target_id"] => Some(ImageLinkLayout::LinkTarget),
+            ["il_from", "il_to", "il_from_namespace"] => Some(ImageLinkLayout::Legacy),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_image_link_layouts() {
+    use crate::iterate_sql_insertions;
+
+    let legacy_sql = br"CREATE TABLE `imagelinks` (
+  `il_from` int(8) unsigned NOT NULL DEFAULT 0,
+  `il_to` varbinary(255) NOT NULL DEFAULT '',
+  `il_from_namespace` int(11) NOT NULL DEFAULT 0,
+  PRIMARY KEY (`il_from`,`il_to`)
+) ENGINE=InnoDB DEFAULT CHARSET=binary;
+INSERT INTO `imagelinks` VALUES (1,'Example.jpg',0),(2,'Wiki.png',10);
+/*!40000 ALTER TABLE `imagelinks` ENABLE KEYS */;
+";
+    assert_eq!(
+        ImageLinkLayout::detect(legacy_sql),
+        Some(ImageLinkLayout::Legacy)
+    );
+    let links: Vec<_> = iterate_sql_insertions::<ImageLink>(legacy_sql)
+        .into_iter()
+        .collect();
+    assert_eq!(
+        links,
+        [
+            ImageLink {
+                from: PageId(1),
+                to: PageTitle("Example.jpg".to_string()),
+                from_namespace: PageNamespace(0),
+            },
+            ImageLink {
+                from: PageId(2),
+                to: PageTitle("Wiki.png".to_string()),
+                from_namespace: PageNamespace(10),
+            },
+        ]
+    );
+
+    let sql = br"CREATE TABLE `imagelinks` (
+  `il_from` int(8) unsigned NOT NULL DEFAULT 0,
+  `il_from_namespace` int(11) NOT NULL DEFAULT 0,
+  `il_target_id` bigint(20) unsigned NOT NULL,
+  PRIMARY KEY (`il_from`,`il_target_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=binary;
+INSERT INTO `imagelinks` VALUES (1,0,20),(2,10,21);
+/*!40000 ALTER TABLE `imagelinks` ENABLE KEYS */;
+";
+    assert_eq!(
+        ImageLinkLayout::detect(sql),
+        Some(ImageLinkLayout::LinkTarget)
+    );
+    let links: Vec<_> = iterate_sql_insertions::<ImageLinkTarget>(sql)
+        .into_iter()
+        .collect();
+    assert_eq!(
+        links,
+        [
+            ImageLinkTarget {
+                from: PageId(1),
+                from_namespace: PageNamespace(0),
+                target: LinkTargetId(20),
+            },
+            ImageLinkTarget {
+                from: PageId(2),
+                from_namespace: PageNamespace(10),
+                target: LinkTargetId(21),
+            },
+        ]
+    );
+}
+
 impl_row_from_sql! {
+    /// Unlike `pagelinks` and `templatelinks`, `iwlinks` has not been migrated
+    /// to the `linktarget` table, so this layout is still current.
+    /// All of its columns are `NOT NULL`.
     iwlinks
-    InterwikiLink<'input> {
+    InterwikiLink<'input> => InterwikiLinkOwned {
         from: PageId,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        prefix: &'input str,
+        /// The interwiki prefix, like `wikt`, without the colon.
+        prefix: &'input str => String,
+        /// The title on the other wiki, which is not normalized by this wiki,
+        /// so it may not follow this wiki's capitalization rules.
+        /// It is `''` for links to the main page of the other wiki, like `[[wikt:]]`.
+        title: PageTitle,
+    }
+}
+
+#[test]
+fn test_interwiki_link() {
+    for (tuple, prefix, title) in &[
+        (&br"(5,'wikt','hello')"[..], "wikt", "hello"),
+        (br"(5,'wikt','')", "wikt", ""),
+        (br"(5,'w','Rock_\'n\'_roll')", "w", "Rock_'n'_roll"),
+    ] {
+        let (_, link) = InterwikiLink::from_sql_tuple(tuple).unwrap();
+        assert_eq!(
+            link,
+            InterwikiLink {
+                from: PageId(5),
+                prefix,
+                title: PageTitle(title.to_string()),
+            }
+        );
+    }
+    assert!(InterwikiLink::from_sql_tuple(br"(5,NULL,'hello')").is_err());
+}
+
+impl_row_from_sql! {
+    job
+    Job<'input> => JobOwned {
+        id: u32,
+        cmd: &'input str => String,
+        namespace: PageNamespace,
         title: PageTitle,
+        timestamp: Option<Timestamp>,
+        /// Serialized parameters of the job.
+        #[cfg_attr(feature = "serialization", serde(with = "crate::field_types::byte_string"))]
+        params: Vec<u8>,
+        random: u32,
+        attempts: u32,
+        token: &'input str => String,
+        token_timestamp: Option<Timestamp>,
+        sha1: Sha1<'input> => String,
+    }
+}
+
+#[test]
+fn test_job() {
+    use bstr::B;
+    let tuple = br#"(1234,'htmlCacheUpdate',10,'Foo','20230101000000','{\"table\":\"templatelinks\"}',987654,1,'',NULL,'')"#;
+    assert_eq!(
+        Job::from_sql_tuple(tuple),
+        Ok((
+            B(""),
+            Job {
+                id: 1234,
+                cmd: "htmlCacheUpdate",
+                namespace: PageNamespace(10),
+                title: PageTitle("Foo".to_string()),
+                timestamp: Some(Timestamp(
+                    chrono::NaiveDate::from_ymd_opt(2023, 1, 1)
+                        .unwrap()
+                        .and_hms_opt(0, 0, 0)
+                        .unwrap()
+                )),
+                params: br#"{"table":"templatelinks"}"#.to_vec(),
+                random: 987654,
+                attempts: 1,
+                token: "",
+                token_timestamp: None,
+                sha1: Sha1(""),
+            }
+        ))
+    );
+}
+
+impl_row_from_sql! {
+    l10n_cache
+    L10nCache<'input> => L10nCacheOwned {
+        lang: &'input str => String,
+        key: &'input str => String,
+        /// Serialized message data.
+        #[cfg_attr(feature = "serialization", serde(with = "crate::field_types::byte_string"))]
+        value: Vec<u8>,
     }
 }
 
+#[test]
+fn test_l10n_cache() {
+    use bstr::B;
+    let tuple = br#"('en','messages:mainpage-description','s:9:\"Main Page\";\nline \'two\'')"#;
+    assert_eq!(
+        L10nCache::from_sql_tuple(tuple),
+        Ok((
+            B(""),
+            L10nCache {
+                lang: "en",
+                key: "messages:mainpage-description",
+                value: b"s:9:\"Main Page\";\nline 'two'".to_vec(),
+            }
+        ))
+    );
+}
+
 impl_row_from_sql! {
+    /// The layout of `langlinks` has not changed since `ll_from`, `ll_lang`, and `ll_title`
+    /// were introduced. Unlike `pagelinks`, it has not been migrated to the `linktarget` table,
+    /// so the title is the full title of the page on the other wiki, including the namespace.
     langlinks
-    LanguageLink<'input> {
+    LanguageLink<'input> => LanguageLinkOwned {
         from: PageId,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        lang: &'input str,
+        /// The language code of the other wiki, like `en`.
+        lang: &'input str => String,
         title: FullPageTitle,
     }
 }
 
+#[test]
+fn test_language_link() {
+    use crate::iterate_sql_insertions;
+
+    let sql = "INSERT INTO `langlinks` VALUES (12,'de','Anarchismus'),(12,'fr','Anarchisme'),(25,'ja','自閉症'),(39,'es','Categoría:Arte');
+/*!40000 ALTER TABLE `langlinks` ENABLE KEYS */;
+";
+    let links: Vec<_> = iterate_sql_insertions::<LanguageLink>(sql.as_bytes())
+        .into_iter()
+        .map(|LanguageLink { from, lang, title }| (from.into_inner(), lang, title.into_inner()))
+        .collect();
+    assert_eq!(
+        links,
+        [
+            (12, "de", "Anarchismus".to_string()),
+            (12, "fr", "Anarchisme".to_string()),
+            (25, "ja", "自閉症".to_string()),
+            (39, "es", "Categoría:Arte".to_string()),
+        ]
+    );
+}
+
 impl_row_from_sql! {
     linktarget
     LinkTarget {
@@ -328,13 +1317,11 @@ impl_row_from_sql! {
 
 impl_row_from_sql! {
     page_restrictions
-    PageRestriction<'input> {
+    PageRestriction<'input> => PageRestrictionOwned {
         id: PageRestrictionId,
         page: PageId,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        r#type: PageAction<'input>,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        level: ProtectionLevel<'input>,
+        r#type: PageAction<'input> => PageAction<'static>,
+        level: ProtectionLevel<'input> => ProtectionLevel<'static>,
         cascade: bool,
         user: Option<u32>,
         expiry: Option<Expiry>,
@@ -343,26 +1330,50 @@ impl_row_from_sql! {
 
 impl_row_from_sql! {
     page
-    Page<'input> {
+    Page<'input> => PageOwned {
         id: PageId,
         namespace: PageNamespace,
         title: PageTitle,
         is_redirect: bool,
         is_new: bool,
-        #[cfg_attr(feature = "serialization", serde(serialize_with = "crate::field_types::serialize_not_nan", deserialize_with = "crate::field_types::deserialize_not_nan"))]
         random: NotNan<f64>,
         touched: Timestamp,
         links_updated: Option<Timestamp>,
         latest: u32,
         len: u32,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        content_model: Option<ContentModel<'input>>,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        lang: Option<&'input str>,
+        content_model: Option<ContentModel<'input>> => Option<ContentModel<'static>>,
+        lang: Option<&'input str> => Option<String>,
     }
 }
 
+#[test]
+fn test_pretty() {
+    let (_, page) = Page::from_sql_tuple(
+        br"(10,0,'Main_Page',0,0,0.5,'20230102030405',NULL,1234,5678,'wikitext',NULL)",
+    )
+    .unwrap();
+    assert_eq!(
+        page.pretty(),
+        r#"id             PageId(10)
+namespace      PageNamespace(0)
+title          PageTitle("Main_Page")
+is_redirect    false
+is_new         false
+random         NotNan(0.5)
+touched        Timestamp(2023-01-02T03:04:05)
+links_updated  None
+latest         1234
+len            5678
+content_model  Some(Wikitext)
+lang           None
+"#
+    );
+}
+
 impl_row_from_sql! {
+    /// This is the layout used since `pl_target_id` was added in MediaWiki 1.41 (2023)
+    /// and `pl_namespace` and `pl_title` were dropped in favor of the `linktarget` table.
+    /// For older dumps, use [`PageLinkLegacy`]. See [`PageLinkLayout`].
     pagelinks
     PageLink {
         from: PageId,
@@ -371,62 +1382,392 @@ impl_row_from_sql! {
     }
 }
 
+impl_row_from_sql! {
+    /// This is the layout used in dumps from before the migration to the `linktarget` table in 2023,
+    /// with the namespace and title of the target in the `pagelinks` table itself.
+    /// For newer dumps, use [`PageLink`]. See [`PageLinkLayout`].
+    pagelinks
+    PageLinkLegacy {
+        from: PageId,
+        namespace: PageNamespace,
+        title: PageTitle,
+        from_namespace: PageNamespace,
+    }
+}
+
+impl_row_from_sql! {
+    /// This is the layout used in dumps from before `pl_from_namespace` was added
+    /// in MediaWiki 1.24 (2014). For newer dumps, use [`PageLinkLegacy`] or [`PageLink`].
+    /// See [`PageLinkLayout`].
+    pagelinks
+    PageLinkVeryOld {
+        from: PageId,
+        namespace: PageNamespace,
+        title: PageTitle,
+    }
+}
+
+/// The layout of the columns in a `pagelinks.sql` dump,
+/// determining whether to parse it with [`PageLink`], [`PageLinkLegacy`], or [`PageLinkVeryOld`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum PageLinkLayout {
+    /// `pl_from`, `pl_from_namespace`, `pl_target_id`, parsed by [`PageLink`].
+    LinkTarget,
+    /// `pl_from`, `pl_namespace`, `pl_title`, `pl_from_namespace`, parsed by [`PageLinkLegacy`].
+    Legacy,
+    /// `pl_from`, `pl_namespace`, `pl_title`, parsed by [`PageLinkVeryOld`].
+    VeryOld,
+}
+
+impl PageLinkLayout {
+    /// Detects the layout from the `CREATE TABLE` statement at the beginning of `pagelinks.sql`
+    /// using [`parse_create_table`](crate::parse_create_table).
+    /// Returns `None` if there is no `CREATE TABLE` statement or the columns match neither layout,
+    /// as in dumps made while the migration was in progress.
+    pub fn detect(sql: &[u8]) -> Option<Self> {
+        let columns = crate::parse_create_table(sql).ok()?;
+        let names: Vec<_> = columns.iter().map(|(name, _)| name.as_str()).collect();
+        match names.as_slice() {
+            ["pl_from", "pl_from_namespace", "pl_target_id"] => Some(PageLinkLayout::LinkTarget),
+            ["pl_from", "pl_namespace", "pl_title", "pl_from_namespace"] => {
+                Some(PageLinkLayout::Legacy)
+            }
+            ["pl_from", "pl_namespace", "pl_title"] => Some(PageLinkLayout::VeryOld),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_page_link_layouts() {
+    use crate::iterate_sql_insertions;
+
+    let legacy_sql = br"CREATE TABLE `pagelinks` (
+  `pl_from` int(8) unsigned NOT NULL DEFAULT 0,
+  `pl_namespace` int(11) NOT NULL DEFAULT 0,
+  `pl_title` varbinary(255) NOT NULL DEFAULT '',
+  `pl_from_namespace` int(11) NOT NULL DEFAULT 0,
+  PRIMARY KEY (`pl_from`,`pl_namespace`,`pl_title`)
+) ENGINE=InnoDB DEFAULT CHARSET=binary;
+INSERT INTO `pagelinks` VALUES (1,0,'Main_Page',0),(2,10,'en-noun',0);
+/*!40000 ALTER TABLE `pagelinks` ENABLE KEYS */;
+";
+    assert_eq!(
+        PageLinkLayout::detect(legacy_sql),
+        Some(PageLinkLayout::Legacy)
+    );
+    let links: Vec<_> = iterate_sql_insertions::<PageLinkLegacy>(legacy_sql).collect();
+    assert_eq!(
+        links,
+        [
+            PageLinkLegacy {
+                from: PageId(1),
+                namespace: PageNamespace(0),
+                title: PageTitle("Main_Page".to_string()),
+                from_namespace: PageNamespace(0),
+            },
+            PageLinkLegacy {
+                from: PageId(2),
+                namespace: PageNamespace(10),
+                title: PageTitle("en-noun".to_string()),
+                from_namespace: PageNamespace(0),
+            },
+        ]
+    );
+
+    let sql = br"CREATE TABLE `pagelinks` (
+  `pl_from` int(8) unsigned NOT NULL DEFAULT 0,
+  `pl_from_namespace` int(11) NOT NULL DEFAULT 0,
+  `pl_target_id` bigint(20) unsigned NOT NULL,
+  PRIMARY KEY (`pl_from`,`pl_target_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=binary;
+INSERT INTO `pagelinks` VALUES (1,0,10),(2,0,11);
+/*!40000 ALTER TABLE `pagelinks` ENABLE KEYS */;
+";
+    assert_eq!(
+        PageLinkLayout::detect(sql),
+        Some(PageLinkLayout::LinkTarget)
+    );
+    let links: Vec<_> = iterate_sql_insertions::<PageLink>(sql).collect();
+    assert_eq!(
+        links,
+        [
+            PageLink {
+                from: PageId(1),
+                from_namespace: PageNamespace(0),
+                target: LinkTargetId(10),
+            },
+            PageLink {
+                from: PageId(2),
+                from_namespace: PageNamespace(0),
+                target: LinkTargetId(11),
+            },
+        ]
+    );
+
+    assert_eq!(
+        PageLinkLayout::detect(b"INSERT INTO `pagelinks` VALUES (1,0,10);\n"),
+        None
+    );
+
+    let very_old_sql = br"CREATE TABLE `pagelinks` (
+  `pl_from` int(8) unsigned NOT NULL default '0',
+  `pl_namespace` int(11) NOT NULL default '0',
+  `pl_title` varchar(255) binary NOT NULL default '',
+  UNIQUE KEY `pl_from` (`pl_from`,`pl_namespace`,`pl_title`)
+) TYPE=InnoDB;
+INSERT INTO `pagelinks` VALUES (1,0,'Main_Page'),(2,10,'en-noun');
+/*!40000 ALTER TABLE `pagelinks` ENABLE KEYS */;
+";
+    assert_eq!(
+        PageLinkLayout::detect(very_old_sql),
+        Some(PageLinkLayout::VeryOld)
+    );
+    let links: Vec<_> = iterate_sql_insertions::<PageLinkVeryOld>(very_old_sql).collect();
+    assert_eq!(
+        links,
+        [
+            PageLinkVeryOld {
+                from: PageId(1),
+                namespace: PageNamespace(0),
+                title: PageTitle("Main_Page".to_string()),
+            },
+            PageLinkVeryOld {
+                from: PageId(2),
+                namespace: PageNamespace(10),
+                title: PageTitle("en-noun".to_string()),
+            },
+        ]
+    );
+}
+
 impl_row_from_sql! {
     page_props
-    PageProperty<'input> {
+    PageProperty<'input> => PagePropertyOwned {
         page: PageId,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        name: &'input str,
+        name: &'input str => String,
+        #[cfg_attr(feature = "serialization", serde(with = "crate::field_types::byte_string"))]
         value: Vec<u8>,
-        #[cfg_attr(feature = "serialization", serde(serialize_with = "crate::field_types::serialize_option_not_nan", deserialize_with = "crate::field_types::deserialize_option_not_nan"))]
         sortkey: Option<NotNan<f64>>,
     }
 }
 
+/// The type of the value of a well-known page property,
+/// as returned by [`PagePropertyType::from_name`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum PagePropertyType {
+    /// A UTF-8 string, such as a `displaytitle` or a `wikibase_item` ID.
+    String,
+    /// An integer stored as a decimal string.
+    Integer,
+    /// A property whose presence is significant and whose value is empty,
+    /// such as `notoc` or `disambiguation`.
+    Flag,
+    /// Arbitrary bytes, such as the gzipped JSON in `templatedata`.
+    Binary,
+}
+
+impl PagePropertyType {
+    /// Returns the expected type of the value of a well-known page property,
+    /// or `None` if the property is not known.
+    pub fn from_name(name: &str) -> Option<Self> {
+        use PagePropertyType::*;
+        let property_type = match name {
+            "defaultsort" | "displaytitle" | "page_image" | "page_image_free" | "wikibase_item"
+            | "wikibase-shortdesc" => String,
+            "kartographer_frames" | "kartographer_links" => Integer,
+            "disambiguation"
+            | "expectedUnconnectedPage"
+            | "expectunusedcategory"
+            | "hiddencat"
+            | "newsectionlink"
+            | "noeditsection"
+            | "noexternallanglinks"
+            | "nogallery"
+            | "noindex"
+            | "nonewsectionlink"
+            | "notoc" => Flag,
+            "templatedata" => Binary,
+            _ => return None,
+        };
+        Some(property_type)
+    }
+}
+
+impl<'input> PageProperty<'input> {
+    /// Returns the value as a string if it is valid UTF-8.
+    pub fn value_as_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.value).ok()
+    }
+
+    /// Returns the value as an integer if it is a valid decimal integer.
+    pub fn value_as_i64(&self) -> Option<i64> {
+        self.value_as_str()?.parse().ok()
+    }
+
+    /// Returns the expected type of the value if the property is well-known.
+    pub fn value_type(&self) -> Option<PagePropertyType> {
+        PagePropertyType::from_name(self.name)
+    }
+
+    /// Whether this is the `hiddencat` property, which is added by `__HIDDENCAT__`
+    /// and whose presence means that the page is a hidden category.
+    pub fn is_hidden_category(&self) -> bool {
+        self.name == "hiddencat"
+    }
+
+    /// Whether this is the `expectedUnconnectedPage` property, which is added by
+    /// `__EXPECTED_UNCONNECTED_PAGE__` and whose presence means that the page
+    /// is not expected to be linked to a Wikidata item.
+    pub fn is_expected_unconnected_page(&self) -> bool {
+        self.name == "expectedUnconnectedPage"
+    }
+
+    /// Returns the Wikidata item ID, like `Q42`, if this is the `wikibase_item` property.
+    pub fn wikibase_item(&self) -> Option<&str> {
+        if self.name == "wikibase_item" {
+            self.value_as_str()
+        } else {
+            None
+        }
+    }
+}
+
+#[test]
+fn test_page_property_values() {
+    let tuple = br"(5,'displaytitle','<i>Homo sapiens</i>',NULL)";
+    let (_, displaytitle) = PageProperty::from_sql_tuple(tuple).unwrap();
+    assert_eq!(displaytitle.value_type(), Some(PagePropertyType::String));
+    assert_eq!(displaytitle.value_as_str(), Some("<i>Homo sapiens</i>"));
+    assert_eq!(displaytitle.value_as_i64(), None);
+
+    let tuple = br"(5,'kartographer_links','3',3)";
+    let (_, links) = PageProperty::from_sql_tuple(tuple).unwrap();
+    assert_eq!(links.value_type(), Some(PagePropertyType::Integer));
+    assert_eq!(links.value_as_i64(), Some(3));
+    assert_eq!(links.sortkey, Some(NotNan::new(3.0).unwrap()));
+}
+
+#[test]
+fn test_page_property_accessors() {
+    let (_, hiddencat) = PageProperty::from_sql_tuple(br"(1243,'hiddencat','',NULL)").unwrap();
+    assert!(hiddencat.is_hidden_category());
+    assert_eq!(hiddencat.value_type(), Some(PagePropertyType::Flag));
+    assert!(!hiddencat.is_expected_unconnected_page());
+    assert_eq!(hiddencat.wikibase_item(), None);
+
+    let (_, item) =
+        PageProperty::from_sql_tuple(br"(1243,'wikibase_item','Q6869278',NULL)").unwrap();
+    assert!(!item.is_hidden_category());
+    assert_eq!(item.wikibase_item(), Some("Q6869278"));
+
+    let (_, unconnected) =
+        PageProperty::from_sql_tuple(br"(7,'expectedUnconnectedPage','',NULL)").unwrap();
+    assert!(unconnected.is_expected_unconnected_page());
+    assert_eq!(unconnected.value_type(), Some(PagePropertyType::Flag));
+}
+
 impl_row_from_sql! {
     protected_titles
-    ProtectedTitle<'input> {
+    ProtectedTitle<'input> => ProtectedTitleOwned {
         namespace: PageNamespace,
         title: PageTitle,
+        /// `pt_user` is `NOT NULL`, but it is 0 if the user is unknown,
+        /// for instance in rows that were imported.
         user: UserId,
+        /// `pt_reason_id` is also `NOT NULL`, and is parsed even if it is 0.
         reason_id: CommentId,
         timestamp: Timestamp,
         expiry: Expiry,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        create_perm: ProtectionLevel<'input>,
+        create_perm: ProtectionLevel<'input> => ProtectionLevel<'static>,
     }
 }
 
+#[test]
+fn test_protected_title() {
+    let (_, title) =
+        ProtectedTitle::from_sql_tuple(br"(0,'Foo',0,0,'20230102030405','infinity','sysop')")
+            .unwrap();
+    assert_eq!(title.user, UserId(0));
+    assert_eq!(title.reason_id, CommentId(0));
+    assert_eq!(title.expiry, Expiry::Infinity);
+}
+
 impl_row_from_sql! {
     redirect
-    Redirect<'input> {
+    Redirect<'input> => RedirectOwned {
         from: PageId,
         namespace: PageNamespace,
         title: PageTitle,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        interwiki: Option<&'input str>,
+        /// The interwiki prefix of the target, if the redirect points to another wiki.
+        /// It is `''` for a redirect to a page on the same wiki, and `NULL` in old rows
+        /// that were added before the column was. Use [`is_interwiki`](Redirect::is_interwiki)
+        /// rather than checking for `None`.
+        interwiki: Option<&'input str> => Option<String>,
         fragment: Option<String>,
     }
 }
 
+impl<'input> Redirect<'input> {
+    /// Whether the redirect points to a page on another wiki, that is,
+    /// whether [`interwiki`](Self::interwiki) is a prefix rather than `''` or `NULL`.
+    pub fn is_interwiki(&self) -> bool {
+        matches!(self.interwiki, Some(prefix) if !prefix.is_empty())
+    }
+}
+
+#[test]
+fn test_redirect_is_interwiki() {
+    for (tuple, is_interwiki) in &[
+        (&br"(1,0,'Foo','',NULL)"[..], false),
+        (br"(2,0,'Foo',NULL,NULL)", false),
+        (br"(3,0,'Foo','wikt','Etymology')", true),
+    ] {
+        let (_, redirect) = Redirect::from_sql_tuple(tuple).unwrap();
+        assert_eq!(redirect.is_interwiki(), *is_interwiki, "{:?}", redirect);
+    }
+}
+
+#[test]
+fn test_to_owned_row() {
+    let sql = br"(1,10,'Foo','w','Etymology')".to_vec();
+    let (_, redirect) = Redirect::from_sql_tuple(&sql).unwrap();
+    let owned = redirect.to_owned_row();
+    drop(sql);
+    let owned = std::thread::spawn(move || owned).join().unwrap();
+    assert_eq!(
+        owned,
+        RedirectOwned {
+            from: PageId(1),
+            namespace: PageNamespace(10),
+            title: PageTitle("Foo".to_string()),
+            interwiki: Some("w".to_string()),
+            fragment: Some("Etymology".to_string()),
+        }
+    );
+
+    let (_, page) = Page::from_sql_tuple(
+        br"(10,0,'Main_Page',0,0,0.5,'20230102030405',NULL,1234,5678,'wikitext','en')",
+    )
+    .unwrap();
+    let owned = page.to_owned_row();
+    assert_eq!(owned.content_model, Some(ContentModel::Wikitext));
+    assert_eq!(owned.lang.as_deref(), Some("en"));
+    assert_eq!(owned.title, page.title);
+}
+
 impl_row_from_sql! {
     sites
-    Site<'input> {
+    Site<'input> => SiteOwned {
         id: u32,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        global_key: &'input str,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        r#type: &'input str,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        group: &'input str,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        source: &'input str,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        language: &'input str,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        protocol: &'input str,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        domain: &'input [u8],
+        global_key: &'input str => String,
+        r#type: &'input str => String,
+        group: &'input str => String,
+        source: &'input str => String,
+        language: &'input str => String,
+        protocol: &'input str => String,
+        #[cfg_attr(feature = "serialization", serde(with = "crate::field_types::byte_string"))]
+        domain: &'input [u8] => Vec<u8>,
         data: String,
         forward: i8,
         config: String,
@@ -434,30 +1775,141 @@ impl_row_from_sql! {
 }
 
 impl_row_from_sql! {
+    /// The counts are `NULL` by default in the database schema, so they are optional,
+    /// though the table in the dumps has a single row with all of them filled in.
+    /// Dumps from before MediaWiki 1.25 had an `ss_total_views` column
+    /// after `ss_total_edits`, which can't be parsed by this struct.
     site_stats
     SiteStats {
+        /// Always 1.
         row_id: u32,
-        total_edits: u64,
-        good_articles: u64,
-        total_pages: u64,
-        users: u64,
-        images: u64,
-        active_users: u64,
+        total_edits: Option<u64>,
+        /// The number of content pages.
+        good_articles: Option<u64>,
+        total_pages: Option<u64>,
+        users: Option<u64>,
+        images: Option<u64>,
+        /// The number of users who edited in the last 30 days.
+        active_users: Option<u64>,
     }
 }
 
+#[test]
+fn test_site_stats() {
+    use crate::iterate_sql_insertions;
+
+    let sql = br"INSERT INTO `site_stats` VALUES (1,90123456,8012345,9876543,5432109,45,3210);
+/*!40000 ALTER TABLE `site_stats` ENABLE KEYS */;
+";
+    let stats: Vec<SiteStats> = iterate_sql_insertions(sql).into_iter().collect();
+    assert_eq!(
+        stats,
+        [SiteStats {
+            row_id: 1,
+            total_edits: Some(90123456),
+            good_articles: Some(8012345),
+            total_pages: Some(9876543),
+            users: Some(5432109),
+            images: Some(45),
+            active_users: Some(3210),
+        }]
+    );
+
+    let (_, stats) = SiteStats::from_sql_tuple(b"(1,NULL,NULL,NULL,NULL,NULL,NULL)").unwrap();
+    assert_eq!(stats.total_edits, None);
+}
+
 impl_row_from_sql! {
     wbc_entity_usage: "Wikibase/Schema/wbc_entity_usage"
-    WikibaseClientEntityUsage<'input> {
+    WikibaseClientEntityUsage<'input> => WikibaseClientEntityUsageOwned {
         row_id: u64,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        entity_id: &'input str,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        aspect: &'input str,
+        entity_id: &'input str => String,
+        aspect: &'input str => String,
         page_id: PageId,
     }
 }
 
+impl_row_from_sql! {
+    wbt_text: "Wikibase/Schema/wbt_text"
+    WbtText {
+        id: u64,
+        text: String,
+    }
+}
+
+impl_row_from_sql! {
+    wbt_text_in_lang: "Wikibase/Schema/wbt_text_in_lang"
+    WbtTextInLang<'input> => WbtTextInLangOwned {
+        id: u64,
+        language: &'input str => String,
+        text_id: u64,
+    }
+}
+
+impl_row_from_sql! {
+    wbt_term_in_lang: "Wikibase/Schema/wbt_term_in_lang"
+    WbtTermInLang {
+        id: u64,
+        type_id: u32,
+        text_in_lang_id: u64,
+    }
+}
+
+impl_row_from_sql! {
+    wbt_type: "Wikibase/Schema/wbt_type"
+    WbtType<'input> => WbtTypeOwned {
+        id: u32,
+        name: &'input str => String,
+    }
+}
+
+#[test]
+fn test_wikibase_term_store() {
+    use bstr::B;
+    assert_eq!(
+        WbtText::from_sql_tuple(br"(118,'Douglas Adams')"),
+        Ok((
+            B(""),
+            WbtText {
+                id: 118,
+                text: "Douglas Adams".to_string(),
+            }
+        ))
+    );
+    assert_eq!(
+        WbtTextInLang::from_sql_tuple(br"(1021,'en',118)"),
+        Ok((
+            B(""),
+            WbtTextInLang {
+                id: 1021,
+                language: "en",
+                text_id: 118,
+            }
+        ))
+    );
+    assert_eq!(
+        WbtTermInLang::from_sql_tuple(br"(5000000001,1,1021)"),
+        Ok((
+            B(""),
+            WbtTermInLang {
+                id: 5000000001,
+                type_id: 1,
+                text_in_lang_id: 1021,
+            }
+        ))
+    );
+    assert_eq!(
+        WbtType::from_sql_tuple(br"(2,'description')"),
+        Ok((
+            B(""),
+            WbtType {
+                id: 2,
+                name: "description",
+            }
+        ))
+    );
+}
+
 #[test]
 fn test_redirect() {
     use bstr::B;
@@ -483,7 +1935,20 @@ fn test_redirect() {
     )
 }
 
+#[cfg(feature = "strict-deserialization")]
+#[test]
+fn test_strict_deserialization() {
+    let json = r#"{"from":605368,"namespace":1,"title":"разблюто","interwiki":"","fragment":null}"#;
+    assert!(serde_json::from_str::<Redirect>(json).is_ok());
+    let json = r#"{"from":605368,"namespace":1,"title":"разблюто","interwiki":"","fragment":null,"extra":0}"#;
+    let error = serde_json::from_str::<Redirect>(json).unwrap_err();
+    assert!(error.to_string().starts_with("unknown field `extra`"));
+}
+
 impl_row_from_sql! {
+    /// This is the layout used since `tl_target_id` was added in MediaWiki 1.38 (2022)
+    /// and `tl_namespace` and `tl_title` were dropped in favor of the `linktarget` table.
+    /// For older dumps, use [`TemplateLinkLegacy`]. See [`TemplateLinkLayout`].
     templatelinks
     TemplateLink {
         from: PageId,
@@ -492,21 +1957,292 @@ impl_row_from_sql! {
     }
 }
 
+impl_row_from_sql! {
+    /// This is the layout used in dumps from before the migration to the `linktarget` table in 2022,
+    /// with the namespace and title of the template in the `templatelinks` table itself.
+    /// For newer dumps, use [`TemplateLink`]. See [`TemplateLinkLayout`].
+    templatelinks
+    TemplateLinkLegacy {
+        from: PageId,
+        namespace: PageNamespace,
+        title: PageTitle,
+        from_namespace: PageNamespace,
+    }
+}
+
+/// The layout of the columns in a `templatelinks.sql` dump,
+/// determining whether to parse it with [`TemplateLink`] or [`TemplateLinkLegacy`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum TemplateLinkLayout {
+    /// `tl_from`, `tl_from_namespace`, `tl_target_id`, parsed by [`TemplateLink`].
+    LinkTarget,
+    /// `tl_from`, `tl_namespace`, `tl_title`, `tl_from_namespace`, parsed by [`TemplateLinkLegacy`].
+    Legacy,
+}
+
+impl TemplateLinkLayout {
+    /// Detects the layout from the `CREATE TABLE` statement at the beginning of `templatelinks.sql`
+    /// using [`parse_create_table`](crate::parse_create_table).
+    /// Returns `None` if there is no `CREATE TABLE` statement or the columns match neither layout,
+    /// as in dumps made while the migration was in progress.
+    pub fn detect(sql: &[u8]) -> Option<Self> {
+        let columns = crate::parse_create_table(sql).ok()?;
+        let names: Vec<_> = columns.iter().map(|(name, _)| name.as_str()).collect();
+        match names.as_slice() {
+            ["tl_from", "tl_from_namespace", "tl_target_id"] => {
+                Some(TemplateLinkLayout::LinkTarget)
+            }
+            ["tl_from", "tl_namespace", "tl_title", "tl_from_namespace"] => {
+                Some(TemplateLinkLayout::Legacy)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_template_link_layouts() {
+    use crate::iterate_sql_insertions;
+
+    let legacy_sql = br"CREATE TABLE `templatelinks` (
+  `tl_from` int(8) unsigned NOT NULL DEFAULT 0,
+  `tl_namespace` int(11) NOT NULL DEFAULT 0,
+  `tl_title` varbinary(255) NOT NULL DEFAULT '',
+  `tl_from_namespace` int(11) NOT NULL DEFAULT 0,
+  PRIMARY KEY (`tl_from`,`tl_namespace`,`tl_title`)
+) ENGINE=InnoDB DEFAULT CHARSET=binary;
+INSERT INTO `templatelinks` VALUES (1,10,'en-noun',0),(2,828,'headword',10);
+/*!40000 ALTER TABLE `templatelinks` ENABLE KEYS */;
+";
+    assert_eq!(
+        TemplateLinkLayout::detect(legacy_sql),
+        Some(TemplateLinkLayout::Legacy)
+    );
+    let links: Vec<_> = iterate_sql_insertions::<TemplateLinkLegacy>(legacy_sql)
+        .into_iter()
+        .collect();
+    assert_eq!(
+        links,
+        [
+            TemplateLinkLegacy {
+                from: PageId(1),
+                namespace: PageNamespace(10),
+                title: PageTitle("en-noun".to_string()),
+                from_namespace: PageNamespace(0),
+            },
+            TemplateLinkLegacy {
+                from: PageId(2),
+                namespace: PageNamespace(828),
+                title: PageTitle("headword".to_string()),
+                from_namespace: PageNamespace(10),
+            },
+        ]
+    );
+
+    let sql = br"CREATE TABLE `templatelinks` (
+  `tl_from` int(8) unsigned NOT NULL DEFAULT 0,
+  `tl_from_namespace` int(11) NOT NULL DEFAULT 0,
+  `tl_target_id` bigint(20) unsigned NOT NULL,
+  PRIMARY KEY (`tl_from`,`tl_target_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=binary;
+INSERT INTO `templatelinks` VALUES (1,0,30),(2,10,31);
+/*!40000 ALTER TABLE `templatelinks` ENABLE KEYS */;
+";
+    assert_eq!(
+        TemplateLinkLayout::detect(sql),
+        Some(TemplateLinkLayout::LinkTarget)
+    );
+    let links: Vec<_> = iterate_sql_insertions::<TemplateLink>(sql)
+        .into_iter()
+        .collect();
+    assert_eq!(
+        links,
+        [
+            TemplateLink {
+                from: PageId(1),
+                from_namespace: PageNamespace(0),
+                target_id: LinkTargetId(30),
+            },
+            TemplateLink {
+                from: PageId(2),
+                from_namespace: PageNamespace(10),
+                target_id: LinkTargetId(31),
+            },
+        ]
+    );
+}
+
+impl_row_from_sql! {
+    uploadstash
+    UploadStash<'input> => UploadStashOwned {
+        id: u32,
+        user: UserId,
+        key: &'input str => String,
+        /// Can contain escaped characters, such as apostrophes in file names.
+        orig_path: String,
+        path: String,
+        source_type: Option<&'input str> => Option<String>,
+        timestamp: Timestamp,
+        status: &'input str => String,
+        chunk_inx: Option<u32>,
+        /// Serialized file properties.
+        #[cfg_attr(feature = "serialization", serde(with = "crate::field_types::byte_string"))]
+        props: Option<Vec<u8>>,
+        size: u32,
+        sha1: Sha1<'input> => String,
+        /// The full MIME type, for instance `image/png`.
+        mime: Option<&'input str> => Option<String>,
+        media_type: Option<MediaType<'input>> => Option<MediaType<'static>>,
+        image_width: Option<u32>,
+        image_height: Option<u32>,
+        image_bits: Option<u16>,
+    }
+}
+
+#[test]
+fn test_upload_stash() {
+    use bstr::B;
+    let tuple = br"(7,42,'1a2b3c.4d5e6f.1.png','/tmp/php\'s upload','mwstore://local-backend/local-temp/1/1a/1a2b3c.4d5e6f.1.png','file','20230102030405','finished',NULL,NULL,1024,'b7xk2fd6z1t1c2c8bbxgqhx4y3fgkhy','image/png','BITMAP',640,480,8)";
+    assert_eq!(
+        UploadStash::from_sql_tuple(tuple),
+        Ok((
+            B(""),
+            UploadStash {
+                id: 7,
+                user: UserId(42),
+                key: "1a2b3c.4d5e6f.1.png",
+                orig_path: "/tmp/php's upload".to_string(),
+                path: "mwstore://local-backend/local-temp/1/1a/1a2b3c.4d5e6f.1.png".to_string(),
+                source_type: Some("file"),
+                timestamp: Timestamp(
+                    chrono::NaiveDate::from_ymd_opt(2023, 1, 2)
+                        .unwrap()
+                        .and_hms_opt(3, 4, 5)
+                        .unwrap()
+                ),
+                status: "finished",
+                chunk_inx: None,
+                props: None,
+                size: 1024,
+                sha1: Sha1("b7xk2fd6z1t1c2c8bbxgqhx4y3fgkhy"),
+                mime: Some("image/png"),
+                media_type: Some(MediaType::Bitmap),
+                image_width: Some(640),
+                image_height: Some(480),
+                image_bits: Some(8),
+            }
+        ))
+    );
+}
+
 impl_row_from_sql! {
     user_former_groups
-    UserFormerGroupMembership<'input> {
+    UserFormerGroupMembership<'input> => UserFormerGroupMembershipOwned {
         user: UserId,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        group: UserGroup<'input>,
+        group: UserGroup<'input> => String,
     }
 }
 
 impl_row_from_sql! {
     user_groups
-    UserGroupMembership<'input> {
+    UserGroupMembership<'input> => UserGroupMembershipOwned {
         user: UserId,
-        #[cfg_attr(feature = "serialization", serde(borrow))]
-        group: UserGroup<'input>,
+        group: UserGroup<'input> => String,
         expiry: Option<Expiry>,
     }
 }
+
+impl<'input> UserGroupMembership<'input> {
+    /// Whether the membership is in effect at `now`: that is,
+    /// whether it never expires (an [`expiry`](Self::expiry) of `NULL` or `'infinity'`)
+    /// or expires after `now`.
+    pub fn is_active_at(&self, now: NaiveDateTime) -> bool {
+        match self.expiry {
+            None | Some(Expiry::Infinity) => true,
+            Some(Expiry::Timestamp(expiry)) => *expiry > now,
+        }
+    }
+}
+
+#[test]
+fn test_user_group_membership_is_active_at() {
+    let now = chrono::NaiveDate::from_ymd_opt(2023, 6, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    for (tuple, is_active) in &[
+        (&b"(1,'sysop',NULL)"[..], true),
+        (b"(1,'sysop','infinity')", true),
+        (b"(2,'bot','20230101000000')", false),
+        (b"(3,'rollbacker','20230601000000')", false),
+        (b"(4,'autopatrolled','20240101000000')", true),
+    ] {
+        let (_, membership) = UserGroupMembership::from_sql_tuple(tuple).unwrap();
+        assert_eq!(membership.is_active_at(now), *is_active, "{:?}", membership);
+    }
+}
+
+macro_rules! row_enum {
+    ($($schema:ident $(<$lifetime:lifetime>)?),+ $(,)?) => {
+        /**
+        A row of any of the tables in this module, as returned by
+        [`iterate_any`](crate::iterate_any) when the table is only known at runtime.
+        Each variant has the same name as the struct it contains.
+        */
+        #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+        pub enum Row<'input> {
+            $(
+                $schema($schema $(<$lifetime>)?),
+            )+
+        }
+
+        $(
+            impl<'input> From<$schema $(<$lifetime>)?> for Row<'input> {
+                fn from(row: $schema $(<$lifetime>)?) -> Self {
+                    Row::$schema(row)
+                }
+            }
+        )+
+    };
+}
+
+row_enum! {
+    Babel<'input>,
+    BotPassword<'input>,
+    Category,
+    CategoryLink<'input>,
+    CategoryLinkLegacy,
+    ChangeTag,
+    ChangeTagDefinition,
+    ExternalLink,
+    ExternalLinkLegacy,
+    GeoTag<'input>,
+    Image<'input>,
+    ImageLink,
+    ImageLinkTarget,
+    InterwikiLink<'input>,
+    Job<'input>,
+    L10nCache<'input>,
+    LanguageLink<'input>,
+    LinkTarget,
+    PageRestriction<'input>,
+    Page<'input>,
+    PageLink,
+    PageLinkLegacy,
+    PageLinkVeryOld,
+    PageProperty<'input>,
+    ProtectedTitle<'input>,
+    Redirect<'input>,
+    Site<'input>,
+    SiteStats,
+    WikibaseClientEntityUsage<'input>,
+    WbtText,
+    WbtTextInLang<'input>,
+    WbtTermInLang,
+    WbtType<'input>,
+    TemplateLink,
+    TemplateLinkLegacy,
+    UploadStash<'input>,
+    UserFormerGroupMembership<'input>,
+    UserGroupMembership<'input>,
+}
@@ -1,21 +1,51 @@
 /*!
 Defines [`memory_map`] to read decompressed MediaWiki SQL files,
-and [`NamespaceMap`] to display a page title prefixed by its namespace name.
+`fetch_dump` to stream them over HTTP when the `download` feature is enabled,
+and [`NamespaceMap`] to display a page title prefixed by its namespace name,
+[`assert_clean_finish`] to check that a whole dump was parsed,
+[`detect_wiki`] and [`detect_server_version`] to read the header of a dump,
+[`fold_with_cap`] to limit the size of an accumulator,
+[`fold_cancellable`] to stop folding when another thread asks,
+[`spawn_parser`] to parse a file in a background thread and send the rows over a channel,
+[`decode_domain_index`] to turn an external link's domain index back into a URL,
+as well as adaptors for iterators over rows, such as [`in_time_range`], [`dedup_by_key`], [`distinct_values`],
+[`sample_every`], [`sample_fraction`], and [`sort_merge_join`],
+and [`compute_site_stats`] to summarize the contents of `page.sql`.
 */
 
 use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashSet},
     fs::File,
+    hash::Hash,
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering as AtomicOrdering},
+        mpsc::Sender,
+    },
+    thread::JoinHandle,
 };
 
+#[cfg(feature = "download")]
+use std::io::{BufRead, BufReader, Read};
+
 use thiserror::Error;
 
+use bstr::{BStr, ByteSlice};
+
+use crate::{
+    field_types::{ContentModel, PageNamespace, Timestamp},
+    is_end_of_insertions, iterate_sql_insertions,
+    schemas::Page,
+    FromSqlTuple, SqlInsertions,
+};
+
 pub use memmap2::Mmap;
 
 /**
 Memory-maps a file, returning a useful message in case of error.
 
-Pass a borrowed memory map to [`iterate_sql_insertions`](crate::iterate_sql_insertions) so that the [schema](crate::schemas) struct
+Pass a borrowed memory map to [`iterate_sql_insertions`] so that the [schema](crate::schemas) struct
 produced by the iterator can borrow from the file's contents. See the [example](crate#example) in the crate documentation.
 
 # Errors
@@ -54,6 +84,1011 @@ impl Error {
     }
 }
 
+/// The error type used by [`assert_clean_finish`].
+#[derive(Debug, Error)]
+pub enum FinishError<'input> {
+    /// A row was malformed.
+    #[error("failed to parse row: {0}")]
+    Parse(crate::Error<'input>),
+    /// The input ended in the middle of a row or statement.
+    #[error("input ended in the middle of a row")]
+    Incomplete,
+    /// Iteration stopped before the end of the `INSERT` statements,
+    /// either because not all rows were consumed or because
+    /// the text after a row was not recognized.
+    #[error("stopped before the end of the INSERT statements, at {:?}", &remaining[..remaining.len().min(80)])]
+    Unfinished { remaining: &'input BStr },
+}
+
+/**
+Checks that iterating over `rows` parsed every row in the `INSERT` statements
+and that only the end of the last statement and the comments after it are left,
+as determined by [`is_end_of_insertions`].
+
+Use this after iterating to fail loudly if rows were silently skipped,
+as when a dump with an unexpected format stops iteration early.
+
+# Errors
+Returns an error if a row failed to parse, the input ended in the middle of a row,
+or parsing stopped early.
+*/
+pub fn assert_clean_finish<Row>(rows: SqlInsertions<'_, Row>) -> Result<(), FinishError<'_>> {
+    match rows.finish() {
+        Ok((remaining, ())) => {
+            if is_end_of_insertions(remaining) {
+                Ok(())
+            } else {
+                Err(FinishError::Unfinished {
+                    remaining: remaining.as_bstr(),
+                })
+            }
+        }
+        Err(nom::Err::Incomplete(_)) => Err(FinishError::Incomplete),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(FinishError::Parse(e)),
+    }
+}
+
+#[test]
+fn test_assert_clean_finish() {
+    use crate::schemas::LinkTarget;
+
+    let sql = br"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,'b');
+INSERT INTO `linktarget` VALUES (3,0,'c');
+/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;
+";
+    let mut rows = iterate_sql_insertions::<LinkTarget>(sql);
+    assert_eq!((&mut rows).count(), 3);
+    assert!(assert_clean_finish(rows).is_ok());
+
+    // Not all rows were consumed.
+    let mut rows = iterate_sql_insertions::<LinkTarget>(sql);
+    assert_eq!((&mut rows).take(2).count(), 2);
+    assert!(matches!(
+        assert_clean_finish(rows),
+        Err(FinishError::Unfinished { .. })
+    ));
+
+    // A comment between statements stops iteration early.
+    let sql = br"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,'b');
+-- a comment
+INSERT INTO `linktarget` VALUES (3,0,'c');
+";
+    let mut rows = iterate_sql_insertions::<LinkTarget>(sql);
+    assert_eq!((&mut rows).count(), 2);
+    let error = assert_clean_finish(rows).unwrap_err();
+    assert!(matches!(error, FinishError::Unfinished { .. }));
+    assert!(error.to_string().contains("-- a comment"));
+
+    // The dump was truncated.
+    let mut rows =
+        iterate_sql_insertions::<LinkTarget>(b"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,");
+    assert_eq!((&mut rows).count(), 1);
+    assert!(matches!(
+        assert_clean_finish(rows),
+        Err(FinishError::Incomplete)
+    ));
+
+    let mut rows = iterate_sql_insertions::<LinkTarget>(
+        b"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,'b',0);",
+    );
+    assert_eq!((&mut rows).count(), 1);
+    assert!(matches!(
+        assert_clean_finish(rows),
+        Err(FinishError::Parse(_))
+    ));
+}
+
+/// The lines of the comments at the beginning of a dump, without the `--` that starts them.
+fn header_comments(sql: &[u8]) -> impl Iterator<Item = &[u8]> {
+    ByteSlice::lines(sql)
+        .take_while(|line| line.is_empty() || line.starts_with(b"--"))
+        .filter_map(|line| line.strip_prefix(b"--"))
+}
+
+/**
+Returns the name of the database that the dump was made from, which is the wiki ID,
+like `enwiki` or `enwiktionary`, from the `Database:` field in the comments at the beginning of the dump:
+
+```text
+-- MySQL dump 10.19  Distrib 10.3.38-MariaDB, for debian-linux-gnu (x86_64)
+--
+-- Host: db1106    Database: enwiki
+```
+
+Returns `None` if the comments don't have a `Database:` field.
+*/
+pub fn detect_wiki(sql: &[u8]) -> Option<&str> {
+    header_comments(sql).find_map(|comment| {
+        let start = comment.find("Database:")? + "Database:".len();
+        let database = comment[start..].trim();
+        if database.is_empty() {
+            None
+        } else {
+            std::str::from_utf8(database).ok()
+        }
+    })
+}
+
+#[test]
+fn test_detect_wiki() {
+    let sql = b"-- MySQL dump 10.19  Distrib 10.3.38-MariaDB, for debian-linux-gnu (x86_64)
+--
+-- Host: db1106    Database: enwiki
+-- ------------------------------------------------------
+-- Server version\t10.6.17-MariaDB-log
+
+/*!40101 SET @OLD_CHARACTER_SET_CLIENT=@@CHARACTER_SET_CLIENT */;
+";
+    assert_eq!(detect_wiki(sql), Some("enwiki"));
+    assert_eq!(
+        detect_wiki(b"-- Host: 10.64.0.1    Database: enwiktionary\r\n--\r\n"),
+        Some("enwiktionary")
+    );
+    assert_eq!(detect_wiki(b"-- Host: db1106    Database: \n"), None);
+    assert_eq!(
+        detect_wiki(b"INSERT INTO `page` VALUES (1);\n-- Database: enwiki\n"),
+        None
+    );
+}
+
+/**
+Returns the version of the MySQL or MariaDB server that the dump was made from,
+like `10.6.17-MariaDB-log`, from the `Server version` line in the comments at the beginning of the dump,
+where it is separated from the version by a tab:
+
+```text
+-- Host: db1106    Database: enwiki
+-- ------------------------------------------------------
+-- Server version    10.6.17-MariaDB-log
+```
+
+Returns `None` if the comments don't have a `Server version` line.
+*/
+pub fn detect_server_version(sql: &[u8]) -> Option<&str> {
+    header_comments(sql).find_map(|comment| {
+        let version = comment.trim_start().strip_prefix(b"Server version")?.trim();
+        if version.is_empty() {
+            None
+        } else {
+            std::str::from_utf8(version).ok()
+        }
+    })
+}
+
+#[test]
+fn test_detect_server_version() {
+    let sql = b"-- MySQL dump 10.19  Distrib 10.3.38-MariaDB, for debian-linux-gnu (x86_64)
+--
+-- Host: db1106    Database: enwiki
+-- ------------------------------------------------------
+-- Server version\t10.6.17-MariaDB-log
+
+/*!40101 SET @OLD_CHARACTER_SET_CLIENT=@@CHARACTER_SET_CLIENT */;
+";
+    assert_eq!(detect_server_version(sql), Some("10.6.17-MariaDB-log"));
+    assert_eq!(
+        detect_server_version(b"-- Server version\t5.7.40\r\n"),
+        Some("5.7.40")
+    );
+    assert_eq!(detect_server_version(b"-- Server version\n"), None);
+    assert_eq!(
+        detect_server_version(b"-- Host: db1106    Database: enwiki\n"),
+        None
+    );
+}
+
+/**
+Streams a SQL dump from a URL, such as one on [dumps.wikimedia.org](https://dumps.wikimedia.org/),
+decompressing it if the URL ends in `.gz`, so that a table can be analyzed
+without downloading the file first. Pass the reader to
+[`StatementReader`](crate::StatementReader) to parse it.
+
+The request has no timeout, because a large dump can take a long time to read.
+
+# Errors
+Fails if the request fails or the server responds with an error status.
+*/
+#[cfg(feature = "download")]
+#[cfg_attr(docsrs, doc(cfg(feature = "download")))]
+pub fn fetch_dump(url: &str) -> Result<impl BufRead, reqwest::Error> {
+    let response = reqwest::blocking::Client::builder()
+        .timeout(None)
+        .build()?
+        .get(url)
+        .send()?
+        .error_for_status()?;
+    let reader: Box<dyn Read + Send> = if url.ends_with(".gz") {
+        Box::new(flate2::read::MultiGzDecoder::new(response))
+    } else {
+        Box::new(response)
+    };
+    Ok(BufReader::new(reader))
+}
+
+#[cfg(feature = "download")]
+#[test]
+#[ignore = "requires network access"]
+fn test_fetch_dump() {
+    use crate::{schemas::SiteStats, StatementReader};
+
+    let dump = fetch_dump(
+        "https://dumps.wikimedia.org/simplewiki/latest/simplewiki-latest-site_stats.sql.gz",
+    )
+    .unwrap();
+    let stats = StatementReader::new(dump)
+        .rows::<SiteStats>()
+        .collect::<std::io::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(stats.len(), 1);
+}
+
+/**
+Filters rows whose timestamp, as returned by `key`, is in the range from `start`
+up to but not including `end`.
+
+```no_run
+# use parse_mediawiki_sql::{
+#     field_types::{NaiveDateTime, Timestamp},
+#     iterate_sql_insertions,
+#     schemas::CategoryLink,
+#     utils::{in_time_range, memory_map},
+# };
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+let category_links_sql = unsafe { memory_map("categorylinks.sql")? };
+let start = Timestamp(NaiveDateTime::parse_from_str("2023-01-01 00:00:00", "%Y-%m-%d %H:%M:%S")?);
+let end = Timestamp(NaiveDateTime::parse_from_str("2024-01-01 00:00:00", "%Y-%m-%d %H:%M:%S")?);
+let added_in_2023 = in_time_range(
+    &mut iterate_sql_insertions::<CategoryLink>(&category_links_sql),
+    start,
+    end,
+    |link| link.timestamp,
+)
+.count();
+# Ok(())
+# }
+```
+*/
+pub fn in_time_range<I, K>(
+    rows: I,
+    start: Timestamp,
+    end: Timestamp,
+    key: K,
+) -> impl Iterator<Item = I::Item>
+where
+    I: IntoIterator,
+    K: Fn(&I::Item) -> Timestamp,
+{
+    rows.into_iter().filter(move |row| {
+        let timestamp = key(row);
+        start <= timestamp && timestamp < end
+    })
+}
+
+#[test]
+fn test_in_time_range() {
+    use crate::{iterate_sql_insertions, schemas::CategoryLink};
+
+    let sql = br"INSERT INTO `categorylinks` VALUES (1,'Nouns','NOUN','2022-12-31 23:59:59','','uca-default-u-kn','page'),(2,'Nouns','NOUN','2023-01-01 00:00:00','','uca-default-u-kn','page'),(3,'Nouns','NOUN','2023-06-15 12:00:00','','uca-default-u-kn','page'),(4,'Nouns','NOUN','2024-01-01 00:00:00','','uca-default-u-kn','page');
+";
+    let timestamp = |s: &str| Timestamp(s.parse().unwrap());
+    let ids: Vec<_> = in_time_range(
+        &mut iterate_sql_insertions::<CategoryLink>(sql),
+        timestamp("2023-01-01T00:00:00"),
+        timestamp("2024-01-01T00:00:00"),
+        |link| link.timestamp,
+    )
+    .map(|link| link.from.into_inner())
+    .collect();
+    assert_eq!(ids, [2, 3]);
+}
+
+/**
+Yields only the first row with each key, such as a primary key like [`PageId`](crate::field_types::PageId),
+skipping rows that duplicate an earlier one.
+
+Every distinct key is kept in a [`HashSet`] until the iterator is dropped,
+so memory usage grows with the number of rows.
+Choose a key that is small and cheap to hash, such as an ID rather than a title.
+*/
+pub fn dedup_by_key<I, K, F>(rows: I, mut key: F) -> impl Iterator<Item = I::Item>
+where
+    I: IntoIterator,
+    K: Eq + Hash,
+    F: FnMut(&I::Item) -> K,
+{
+    let mut seen = HashSet::new();
+    rows.into_iter().filter(move |row| seen.insert(key(row)))
+}
+
+#[test]
+fn test_dedup_by_key() {
+    let sql = br"INSERT INTO `page` VALUES (1,0,'A',0,0,0.1,'20230101000000',NULL,1,1,'wikitext',NULL),(2,0,'B',0,0,0.2,'20230101000000',NULL,2,2,'wikitext',NULL);
+INSERT INTO `page` VALUES (1,0,'A',0,0,0.1,'20230102000000',NULL,3,3,'wikitext',NULL),(3,0,'C',0,0,0.3,'20230101000000',NULL,4,4,'wikitext',NULL);
+";
+    let pages: Vec<_> = dedup_by_key(&mut iterate_sql_insertions::<Page>(sql), |page| page.id)
+        .map(|page| (page.id.into_inner(), page.latest))
+        .collect();
+    assert_eq!(pages, [(1, 1), (2, 2), (3, 4)]);
+}
+
+/**
+Collects the distinct values of a field, or of anything else computed from each row,
+such as all of the [`ContentModel`]s in `page.sql` or the [`UserGroup`](crate::field_types::UserGroup)s
+in `user_groups.sql`, in sorted order.
+
+The set holds every distinct value, so choose a field with few of them,
+and pass an iterator over a mutable reference to check for errors afterwards.
+To count how many rows have each value, fold the rows into a map instead.
+*/
+pub fn distinct_values<I, K, F>(rows: I, key: F) -> BTreeSet<K>
+where
+    I: IntoIterator,
+    K: Ord,
+    F: FnMut(I::Item) -> K,
+{
+    rows.into_iter().map(key).collect()
+}
+
+#[test]
+fn test_distinct_values() {
+    use crate::schemas::UserGroupMembership;
+
+    let sql = br"INSERT INTO `page` VALUES (1,0,'A',0,0,0.1,'20230101000000',NULL,1,1,'wikitext',NULL),(2,8,'B.css',0,0,0.2,'20230101000000',NULL,2,2,'css',NULL),(3,0,'C',0,0,0.3,'20230101000000',NULL,4,4,'wikitext',NULL),(4,0,'D',0,0,0.4,'20230101000000',NULL,5,5,NULL,NULL);
+";
+    let mut rows = iterate_sql_insertions::<Page>(sql);
+    let content_models = distinct_values(&mut rows, |page| page.content_model);
+    assert_clean_finish(rows).unwrap();
+    assert_eq!(
+        content_models.into_iter().collect::<Vec<_>>(),
+        [None, Some(ContentModel::Wikitext), Some(ContentModel::Css)]
+    );
+
+    let sql = br"INSERT INTO `user_groups` VALUES (1,'sysop',NULL),(2,'bot',NULL),(3,'sysop',NULL),(3,'bureaucrat',NULL);
+";
+    let groups = distinct_values(
+        iterate_sql_insertions::<UserGroupMembership>(sql),
+        |membership| membership.group.into_inner(),
+    );
+    assert_eq!(
+        groups.into_iter().collect::<Vec<_>>(),
+        ["bot", "bureaucrat", "sysop"]
+    );
+}
+
+/// The error returned by [`fold_with_cap`] when the accumulator grows too large.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Error)]
+#[error("accumulator exceeded the limit of {cap} entries after {rows} rows")]
+pub struct CapExceeded {
+    /// The maximum number of entries.
+    pub cap: usize,
+    /// The number of rows that had been folded into the accumulator when it exceeded the cap.
+    pub rows: usize,
+}
+
+/**
+Folds rows into an accumulator like [`Iterator::fold`], but stops with an error
+as soon as the number of entries in the accumulator, as measured by `len`, exceeds `cap`.
+
+Use this when collecting rows into a map or other collection from a dump that might be
+larger than expected, so that a command-line tool fails with a message
+instead of running out of memory.
+
+# Errors
+Returns [`CapExceeded`] if `len` of the accumulator is greater than `cap` after any row.
+*/
+pub fn fold_with_cap<I, Acc, L, F>(
+    rows: I,
+    init: Acc,
+    cap: usize,
+    mut len: L,
+    mut f: F,
+) -> Result<Acc, CapExceeded>
+where
+    I: IntoIterator,
+    L: FnMut(&Acc) -> usize,
+    F: FnMut(Acc, I::Item) -> Acc,
+{
+    let mut acc = init;
+    for (i, row) in rows.into_iter().enumerate() {
+        acc = f(acc, row);
+        if len(&acc) > cap {
+            return Err(CapExceeded { cap, rows: i + 1 });
+        }
+    }
+    Ok(acc)
+}
+
+#[test]
+fn test_fold_with_cap() {
+    use crate::schemas::LinkTarget;
+    use std::collections::HashMap;
+
+    let sql =
+        br"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,'b'),(3,0,'a'),(4,10,'c'),(5,0,'d');
+/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;
+";
+    let count_titles = |cap| {
+        fold_with_cap(
+            iterate_sql_insertions::<LinkTarget>(sql),
+            HashMap::new(),
+            cap,
+            HashMap::len,
+            |mut counts, LinkTarget { title, .. }| {
+                *counts.entry(title).or_insert(0) += 1;
+                counts
+            },
+        )
+    };
+    assert_eq!(count_titles(4).map(|counts| counts.len()), Ok(4));
+    assert_eq!(count_titles(3), Err(CapExceeded { cap: 3, rows: 5 }));
+    assert_eq!(
+        count_titles(1).unwrap_err().to_string(),
+        "accumulator exceeded the limit of 1 entries after 2 rows"
+    );
+}
+
+/// The error returned by [`fold_cancellable`] when the cancellation flag was set.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Cancelled<Acc> {
+    /// The accumulator with the rows that were folded before cancellation.
+    pub acc: Acc,
+    /// The number of rows that had been folded into the accumulator.
+    pub rows: usize,
+}
+
+impl<Acc> std::fmt::Display for Cancelled<Acc> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cancelled after {} rows", self.rows)
+    }
+}
+
+impl<Acc: std::fmt::Debug> std::error::Error for Cancelled<Acc> {}
+
+/// How many rows [`fold_cancellable`] folds between checks of the cancellation flag.
+pub const CANCELLATION_CHECK_INTERVAL: usize = 1024;
+
+/**
+Folds rows into an accumulator like [`Iterator::fold`], but stops early
+if `cancelled` has been set, for instance by another thread
+or by a Ctrl-C handler in an interactive tool.
+
+The flag is checked before the first row and then every
+[`CANCELLATION_CHECK_INTERVAL`] rows, so that checking it doesn't slow down parsing.
+
+# Errors
+Returns [`Cancelled`], containing the accumulator so far, if the flag was set.
+*/
+pub fn fold_cancellable<I, Acc, F>(
+    rows: I,
+    cancelled: &AtomicBool,
+    init: Acc,
+    mut f: F,
+) -> Result<Acc, Cancelled<Acc>>
+where
+    I: IntoIterator,
+    F: FnMut(Acc, I::Item) -> Acc,
+{
+    let mut acc = init;
+    for (i, row) in rows.into_iter().enumerate() {
+        if i % CANCELLATION_CHECK_INTERVAL == 0 && cancelled.load(AtomicOrdering::Relaxed) {
+            return Err(Cancelled { acc, rows: i });
+        }
+        acc = f(acc, row);
+    }
+    Ok(acc)
+}
+
+#[test]
+fn test_fold_cancellable() {
+    use crate::schemas::LinkTarget;
+
+    let sql = br"INSERT INTO `linktarget` VALUES (1,0,'a'),(2,0,'b'),(3,0,'c');";
+    let count = |cancelled: &AtomicBool| {
+        fold_cancellable(
+            iterate_sql_insertions::<LinkTarget>(sql),
+            cancelled,
+            0,
+            |count, _| count + 1,
+        )
+    };
+    assert_eq!(count(&AtomicBool::new(false)), Ok(3));
+    assert_eq!(
+        count(&AtomicBool::new(true)),
+        Err(Cancelled { acc: 0, rows: 0 })
+    );
+
+    let cancelled = AtomicBool::new(false);
+    let result = fold_cancellable(0.., &cancelled, 0, |count, i| {
+        if i == 2000 {
+            cancelled.store(true, AtomicOrdering::Relaxed);
+        }
+        count + 1
+    });
+    assert_eq!(
+        result,
+        Err(Cancelled {
+            acc: 2 * CANCELLATION_CHECK_INTERVAL,
+            rows: 2 * CANCELLATION_CHECK_INTERVAL
+        })
+    );
+
+    let cancelled = AtomicBool::new(false);
+    let result = std::thread::scope(|scope| {
+        let counter = scope.spawn(|| fold_cancellable(0.., &cancelled, 0u64, |sum, i| sum + i));
+        cancelled.store(true, AtomicOrdering::Relaxed);
+        counter.join().unwrap()
+    });
+    assert!(result.is_err());
+}
+
+/// The error returned by the thread spawned by [`spawn_parser`].
+#[derive(Debug, Error)]
+pub enum SpawnParserError {
+    /// The file could not be opened or memory-mapped.
+    #[error(transparent)]
+    Map(#[from] Error),
+    /// Parsing stopped before the end of the `INSERT` statements,
+    /// as described by [`assert_clean_finish`].
+    #[error("{message} after {rows} rows")]
+    Parse {
+        /// The number of rows sent before the error.
+        rows: usize,
+        /// The message of the [`FinishError`], which can't be kept after the file is unmapped.
+        message: String,
+    },
+}
+
+/**
+Memory-maps the file at `path` in a new thread, parses its rows, and sends them over `sender`,
+so that another thread can process the rows as they arrive.
+
+The rows must not borrow from the file, which is unmapped when the thread finishes.
+Use the schemas that don't have a lifetime parameter, like [`Category`](crate::schemas::Category),
+or the owned versions of the others, like [`PageOwned`](crate::schemas::PageOwned),
+which are parsed by copying the fields as described in [`ToOwnedField`](crate::field_types::ToOwnedField).
+
+The thread returns the number of rows sent. It stops early without an error
+if the receiver is dropped.
+
+```no_run
+use parse_mediawiki_sql::{schemas::PageOwned, utils::spawn_parser};
+let (sender, receiver) = std::sync::mpsc::channel::<PageOwned>();
+let parser = unsafe { spawn_parser("page.sql", sender) };
+let redirects = receiver.into_iter().filter(|page| page.is_redirect).count();
+let pages = parser.join().expect("parser thread panicked")?;
+println!("{} of {} pages are redirects", redirects, pages);
+# Ok::<_, parse_mediawiki_sql::utils::SpawnParserError>(())
+```
+
+# Errors
+The thread returns an error if the file could not be memory-mapped
+or if parsing stopped before the end of the `INSERT` statements.
+
+# Safety
+Inherits unsafe annotation from [`Mmap::map`].
+*/
+pub unsafe fn spawn_parser<Row, P>(
+    path: P,
+    sender: Sender<Row>,
+) -> JoinHandle<Result<usize, SpawnParserError>>
+where
+    Row: for<'input> FromSqlTuple<'input> + Send + 'static,
+    P: Into<PathBuf>,
+{
+    let path = path.into();
+    std::thread::spawn(move || {
+        let sql = memory_map(path)?;
+        let mut rows = iterate_sql_insertions::<Row>(&sql);
+        let mut sent = 0;
+        for row in &mut rows {
+            if sender.send(row).is_err() {
+                return Ok(sent);
+            }
+            sent += 1;
+        }
+        assert_clean_finish(rows).map_err(|e| SpawnParserError::Parse {
+            rows: sent,
+            message: e.to_string(),
+        })?;
+        Ok(sent)
+    })
+}
+
+#[test]
+fn test_spawn_parser() {
+    use crate::{
+        field_types::{PageId, PageNamespace, PageTitle},
+        schemas::{Category, RedirectOwned},
+    };
+    use std::sync::mpsc::channel;
+
+    let dir = std::env::temp_dir().join(format!("parse-mediawiki-sql-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let redirect_path = dir.join("redirect.sql");
+    std::fs::write(
+        &redirect_path,
+        br"INSERT INTO `redirect` VALUES (1,0,'Foo','',NULL),(2,10,'Bar',NULL,'Baz');",
+    )
+    .unwrap();
+    let (sender, receiver) = channel::<RedirectOwned>();
+    let parser = unsafe { spawn_parser(&redirect_path, sender) };
+    let redirects: Vec<_> = receiver.into_iter().collect();
+    assert_eq!(parser.join().unwrap().unwrap(), 2);
+    assert_eq!(
+        redirects,
+        [
+            RedirectOwned {
+                from: PageId(1),
+                namespace: PageNamespace(0),
+                title: PageTitle("Foo".to_string()),
+                interwiki: Some(String::new()),
+                fragment: None,
+            },
+            RedirectOwned {
+                from: PageId(2),
+                namespace: PageNamespace(10),
+                title: PageTitle("Bar".to_string()),
+                interwiki: None,
+                fragment: Some("Baz".to_string()),
+            },
+        ]
+    );
+
+    let category_path = dir.join("category.sql");
+    std::fs::write(
+        &category_path,
+        br"INSERT INTO `category` VALUES (1,'Nouns',3,0,0),(2,'Verbs',x,0,0);",
+    )
+    .unwrap();
+    let (sender, receiver) = channel::<Category>();
+    let parser = unsafe { spawn_parser(&category_path, sender) };
+    assert_eq!(receiver.into_iter().count(), 1);
+    assert!(matches!(
+        parser.join().unwrap(),
+        Err(SpawnParserError::Parse { rows: 1, .. })
+    ));
+
+    let (sender, _) = channel::<Category>();
+    let parser = unsafe { spawn_parser(dir.join("missing.sql"), sender) };
+    assert!(matches!(
+        parser.join().unwrap(),
+        Err(SpawnParserError::Map(_))
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/**
+Yields every `n`th row, starting with the first,
+to explore a dump quickly without doing the rest of the work for every row.
+
+# Panics
+Panics if `n` is 0.
+*/
+pub fn sample_every<I: IntoIterator>(rows: I, n: usize) -> impl Iterator<Item = I::Item> {
+    assert!(n > 0, "must sample at least every row");
+    rows.into_iter().step_by(n)
+}
+
+/**
+Yields each row with probability `fraction`, independently of the others,
+so that roughly `fraction` of the rows are kept.
+
+Rows are chosen by a small pseudorandom number generator seeded with `seed`,
+so the same seed selects the same rows from the same dump.
+Unlike a filter on a random field like [`Page::random`],
+this works for any table.
+
+# Panics
+Panics if `fraction` is not between 0 and 1.
+*/
+pub fn sample_fraction<I: IntoIterator>(
+    rows: I,
+    fraction: f64,
+    seed: u64,
+) -> impl Iterator<Item = I::Item> {
+    assert!(
+        (0.0..=1.0).contains(&fraction),
+        "fraction of rows to sample must be between 0 and 1"
+    );
+    // SplitMix64: https://prng.di.unimi.it/splitmix64.c
+    let mut state = seed;
+    let mut next_f64 = move || {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        // The top 53 bits give a uniformly distributed float in [0, 1).
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    };
+    rows.into_iter().filter(move |_| next_f64() < fraction)
+}
+
+#[test]
+fn test_sample() {
+    use crate::schemas::LinkTarget;
+    use std::fmt::Write as _;
+
+    let mut sql = String::from("INSERT INTO `linktarget` VALUES ");
+    for id in 1..=10_000 {
+        if id > 1 {
+            sql.push(',');
+        }
+        write!(sql, "({},0,'Title_{}')", id, id).unwrap();
+    }
+    sql.push_str(";\n/*!40000 ALTER TABLE `linktarget` ENABLE KEYS */;\n");
+    let ids = || {
+        iterate_sql_insertions::<LinkTarget>(sql.as_bytes())
+            .into_iter()
+            .map(|row| row.id.into_inner())
+    };
+
+    let every: Vec<_> = sample_every(ids(), 100).collect();
+    assert_eq!(every.len(), 100);
+    assert_eq!(every[..3], [1, 101, 201]);
+
+    let sampled: Vec<_> = sample_fraction(ids(), 0.25, 42).collect();
+    assert!(
+        (2_250..=2_750).contains(&sampled.len()),
+        "sampled {} of 10,000 rows",
+        sampled.len()
+    );
+    assert_eq!(
+        sample_fraction(ids(), 0.25, 42).collect::<Vec<_>>(),
+        sampled
+    );
+    assert_ne!(
+        sample_fraction(ids(), 0.25, 43).collect::<Vec<_>>(),
+        sampled
+    );
+    assert_eq!(sample_fraction(ids(), 0.0, 42).count(), 0);
+    assert_eq!(sample_fraction(ids(), 1.0, 42).count(), 10_000);
+}
+
+/**
+Joins two iterators of rows that are both sorted by the join key,
+yielding each row from `left` paired with the row from `right` that has the same key.
+Rows without a match on the other side are skipped, as in an SQL inner join.
+
+Only one row of each iterator is held at a time, so this can join two dumps
+that are too big to load into a map, as long as both are sorted by the join key.
+Dumps are sorted by their primary key, so this works when `right` is joined on its primary key,
+such as `page_props` joined to `page` on [`PageId`](crate::field_types::PageId).
+Keys in `right` must be unique, but keys in `left` may repeat,
+in which case the matching row from `right` is cloned for each of them.
+If either iterator is not sorted, rows are silently missed.
+*/
+pub fn sort_merge_join<L, R, K, FL, FR>(
+    left: L,
+    right: R,
+    mut left_key: FL,
+    mut right_key: FR,
+) -> impl Iterator<Item = (L::Item, R::Item)>
+where
+    L: IntoIterator,
+    R: IntoIterator,
+    R::Item: Clone,
+    K: Ord,
+    FL: FnMut(&L::Item) -> K,
+    FR: FnMut(&R::Item) -> K,
+{
+    let mut left = left.into_iter();
+    let mut right = right.into_iter().peekable();
+    std::iter::from_fn(move || {
+        'left: for left_row in left.by_ref() {
+            let key = left_key(&left_row);
+            while let Some(right_row) = right.peek() {
+                match right_key(right_row).cmp(&key) {
+                    Ordering::Less => {
+                        right.next();
+                    }
+                    Ordering::Equal => return Some((left_row, right_row.clone())),
+                    Ordering::Greater => continue 'left,
+                }
+            }
+            return None;
+        }
+        None
+    })
+}
+
+#[test]
+fn test_sort_merge_join() {
+    use crate::schemas::PageProperty;
+
+    let page_sql = br"INSERT INTO `page` VALUES (1,0,'A',0,0,0.1,'20230101000000',NULL,1,1,'wikitext',NULL),(2,0,'B',0,0,0.2,'20230101000000',NULL,2,2,'wikitext',NULL),(4,10,'D',0,0,0.4,'20230101000000',NULL,4,4,'wikitext',NULL),(5,0,'E',0,0,0.5,'20230101000000',NULL,5,5,'wikitext',NULL);
+/*!40000 ALTER TABLE `page` ENABLE KEYS */;
+";
+    let page_props_sql = br"INSERT INTO `page_props` VALUES (1,'defaultsort','a',NULL),(1,'wikibase_item','Q1',NULL),(3,'defaultsort','c',NULL),(4,'noindex','',NULL),(6,'defaultsort','f',NULL);
+/*!40000 ALTER TABLE `page_props` ENABLE KEYS */;
+";
+    let joined: Vec<_> = sort_merge_join(
+        &mut iterate_sql_insertions::<PageProperty>(page_props_sql),
+        &mut iterate_sql_insertions::<Page>(page_sql),
+        |prop| prop.page,
+        |page| page.id,
+    )
+    .map(|(prop, page)| (prop.name, page.title.into_inner()))
+    .collect();
+    assert_eq!(
+        joined,
+        [
+            ("defaultsort", "A".to_string()),
+            ("wikibase_item", "A".to_string()),
+            ("noindex", "D".to_string()),
+        ]
+    );
+}
+
+/// Summary of the pages in `page.sql`, returned by [`compute_site_stats`].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SiteStatsSummary<'input> {
+    /// The total number of pages, including redirects.
+    pub pages: u64,
+    /// The number of redirects.
+    pub redirects: u64,
+    /// The sum of the lengths of all pages in bytes.
+    pub total_length: u64,
+    /// The number of pages in each namespace, including redirects.
+    pub pages_by_namespace: BTreeMap<PageNamespace, u64>,
+    /// The number of redirects in each namespace.
+    pub redirects_by_namespace: BTreeMap<PageNamespace, u64>,
+    /// The number of pages with each content model.
+    /// `None` is the default content model of the page's namespace.
+    pub content_models: BTreeMap<Option<ContentModel<'input>>, u64>,
+}
+
+/**
+Counts the pages in `page.sql` by namespace and content model
+and adds up their lengths in a single pass.
+
+Parse errors are not reported: counting stops at the first row that fails to parse,
+so a truncated or malformed dump silently gives partial counts.
+When that matters, fold the rows with [`fold_sql_insertions`](crate::fold_sql_insertions),
+which returns the error along with the accumulator, or iterate over the [`SqlInsertions`]
+and check them afterward with [`assert_clean_finish`].
+
+# Panics
+Panics if the input does not contain `INSERT INTO`, like [`iterate_sql_insertions`].
+*/
+pub fn compute_site_stats(page_sql: &[u8]) -> SiteStatsSummary<'_> {
+    iterate_sql_insertions(page_sql).fold(
+        SiteStatsSummary::default(),
+        |mut summary,
+         Page {
+             namespace,
+             is_redirect,
+             len,
+             content_model,
+             ..
+         }| {
+            summary.pages += 1;
+            summary.total_length += u64::from(len);
+            *summary.pages_by_namespace.entry(namespace).or_insert(0) += 1;
+            if is_redirect {
+                summary.redirects += 1;
+                *summary.redirects_by_namespace.entry(namespace).or_insert(0) += 1;
+            }
+            *summary.content_models.entry(content_model).or_insert(0) += 1;
+            summary
+        },
+    )
+}
+
+#[test]
+fn test_compute_site_stats() {
+    let sql = br"INSERT INTO `page` VALUES (1,0,'Main_Page',0,0,0.1,'20230101000000',NULL,1,100,'wikitext',NULL),(2,0,'Main',1,0,0.2,'20230101000000',NULL,2,20,'wikitext',NULL),(3,828,'foo',0,0,0.3,'20230101000000',NULL,3,300,'Scribunto',NULL);
+INSERT INTO `page` VALUES (4,10,'bar',0,1,0.4,'20230101000000',NULL,4,40,NULL,NULL);
+";
+    let summary = compute_site_stats(sql);
+    assert_eq!(
+        summary,
+        SiteStatsSummary {
+            pages: 4,
+            redirects: 1,
+            total_length: 460,
+            pages_by_namespace: [
+                (PageNamespace::MAIN, 2),
+                (PageNamespace::TEMPLATE, 1),
+                (PageNamespace(828), 1)
+            ]
+            .iter()
+            .copied()
+            .collect(),
+            redirects_by_namespace: [(PageNamespace::MAIN, 1)].iter().copied().collect(),
+            content_models: [
+                (None, 1),
+                (Some(ContentModel::Wikitext), 2),
+                (Some(ContentModel::Scribunto), 1)
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        }
+    );
+}
+
+/**
+Decodes a domain index from the `el_to_domain_index` column of [`ExternalLink`](crate::schemas::ExternalLink)
+or the legacy `el_index` column of [`ExternalLinkLegacy`](crate::schemas::ExternalLinkLegacy)
+into the beginning of a URL, like `https://en.wikipedia.org` for `https://org.wikipedia.en.`.
+
+The labels of the host name are put back in order and its trailing dot is removed,
+as in [`ExternalLink::url`](crate::schemas::ExternalLink::url),
+and percent-encoded bytes in the host name are decoded.
+The port and, in `el_index`, the path are left as they are.
+Invalid UTF-8 is replaced with `U+FFFD REPLACEMENT CHARACTER`.
+*/
+pub fn decode_domain_index(bytes: &[u8]) -> String {
+    let mut url = String::with_capacity(bytes.len());
+    crate::schemas::push_domain_index(&mut url, &String::from_utf8_lossy(bytes), |url, host| {
+        crate::schemas::push_host(url, &percent_decode(host))
+    });
+    url
+}
+
+fn percent_decode(s: &str) -> std::borrow::Cow<'_, str> {
+    if !s.contains('%') {
+        return s.into();
+    }
+    let mut decoded = Vec::with_capacity(s.len());
+    let mut bytes = s.as_bytes();
+    while let Some((&byte, rest)) = bytes.split_first() {
+        let hex = rest
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(decoded_byte) if byte == b'%' => {
+                decoded.push(decoded_byte);
+                bytes = &rest[2..];
+            }
+            _ => {
+                decoded.push(byte);
+                bytes = rest;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned().into()
+}
+
+#[test]
+fn test_decode_domain_index() {
+    assert_eq!(
+        decode_domain_index(b"https://org.wikipedia.en."),
+        "https://en.wikipedia.org"
+    );
+    assert_eq!(
+        decode_domain_index(b"http://com.example.www.:8080"),
+        "http://www.example.com:8080"
+    );
+    assert_eq!(
+        decode_domain_index(b"http://V4.192.168.0.1.:80"),
+        "http://192.168.0.1:80"
+    );
+    assert_eq!(
+        decode_domain_index(b"http://V4.10.0.0.1."),
+        "http://10.0.0.1"
+    );
+    assert_eq!(
+        decode_domain_index(b"https://org.example.:443/wiki/a.b%2Fc"),
+        "https://example.org:443/wiki/a.b%2Fc"
+    );
+    assert_eq!(
+        decode_domain_index(b"http://org.%C3%A9xample."),
+        "http://\u{e9}xample.org"
+    );
+    assert_eq!(
+        decode_domain_index(b"mailto:org.example.@user"),
+        "mailto:user@example.org"
+    );
+    assert_eq!(
+        decode_domain_index(b"https://org.ex\xffample."),
+        "https://ex\u{FFFD}ample.org"
+    );
+}
+
 pub use mwtitle::{NamespaceMap, Title};
 
 pub trait NamespaceMapExt {
@@ -62,6 +1097,18 @@ pub trait NamespaceMapExt {
         namespace: crate::field_types::PageNamespace,
         title: &crate::field_types::PageTitle,
     ) -> String;
+
+    /// Displays the target of a redirect as it would appear in a link,
+    /// with the interwiki prefix and the fragment if they are not empty:
+    /// `interwiki:Namespace:Title#Fragment`.
+    fn redirect_target(&self, redirect: &crate::schemas::Redirect) -> String;
+
+    /// Creates a map from namespace IDs and names, without aliases,
+    /// in which the first letter of every title is capitalized.
+    /// Useful in tests or when the wiki's siteinfo JSON isn't available.
+    fn from_pairs<I: IntoIterator<Item = (i32, String)>>(pairs: I) -> Self
+    where
+        Self: Sized;
 }
 
 impl NamespaceMapExt for NamespaceMap {
@@ -76,4 +1123,90 @@ impl NamespaceMapExt for NamespaceMap {
         ))
         .expect("invalid namespace ID")
     }
+
+    fn redirect_target(&self, redirect: &crate::schemas::Redirect) -> String {
+        let mut title = Title::new_unchecked(
+            redirect.namespace.into_inner(),
+            <&String>::from(&redirect.title),
+        );
+        if let Some(fragment) = redirect.fragment.as_deref().filter(|f| !f.is_empty()) {
+            title = title.with_fragment(fragment);
+        }
+        let target = self
+            .to_pretty_with_fragment(&title)
+            .expect("invalid namespace ID");
+        match redirect.interwiki.filter(|i| !i.is_empty()) {
+            Some(interwiki) => format!("{}:{}", interwiki, target),
+            None => target,
+        }
+    }
+
+    fn from_pairs<I: IntoIterator<Item = (i32, String)>>(pairs: I) -> Self {
+        NamespaceMap::from_iters(
+            pairs.into_iter().map(|(id, name)| {
+                vec![
+                    ("id".to_string(), id.to_string()),
+                    ("case".to_string(), "first-letter".to_string()),
+                    ("name".to_string(), name),
+                ]
+            }),
+            Vec::new(),
+        )
+        .expect("namespaces with an ID, case, and name and no aliases should be valid")
+    }
+}
+
+#[test]
+fn test_namespace_map_from_pairs() {
+    use crate::field_types::{PageNamespace, PageTitle};
+
+    let namespace_map =
+        NamespaceMap::from_pairs(vec![(0, String::new()), (10, "Template".to_string())]);
+    assert_eq!(
+        namespace_map.pretty_title(PageNamespace(10), &PageTitle("en-noun".to_string())),
+        "Template:en-noun"
+    );
+    assert_eq!(
+        namespace_map.pretty_title(PageNamespace(0), &PageTitle("free_software".to_string())),
+        "free software"
+    );
+}
+
+#[test]
+fn test_redirect_target() {
+    use crate::{
+        field_types::{PageId, PageNamespace, PageTitle},
+        schemas::Redirect,
+    };
+
+    let namespace_map =
+        NamespaceMap::from_pairs(vec![(0, String::new()), (10, "Template".to_string())]);
+    let redirect = |namespace, title: &str, interwiki, fragment: Option<&str>| Redirect {
+        from: PageId(1),
+        namespace: PageNamespace(namespace),
+        title: PageTitle(title.to_string()),
+        interwiki,
+        fragment: fragment.map(String::from),
+    };
+    for (redirect, target) in &[
+        (
+            redirect(10, "en-noun", Some(""), Some("")),
+            "Template:en-noun",
+        ),
+        (redirect(10, "en-noun", None, None), "Template:en-noun"),
+        (
+            redirect(0, "free_software", Some(""), Some("Etymology")),
+            "free software#Etymology",
+        ),
+        (
+            redirect(0, "Free_software", Some("w"), None),
+            "w:Free software",
+        ),
+        (
+            redirect(0, "Free_software", Some("w"), Some("History")),
+            "w:Free software#History",
+        ),
+    ] {
+        assert_eq!(&namespace_map.redirect_target(redirect), target);
+    }
 }